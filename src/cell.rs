@@ -9,6 +9,25 @@ pub const FIRE_CELL: u8 = 5;
 pub const GLASS_CELL: u8 = 6;
 pub const SMOKE_CELL: u8 = 7;
 pub const STEAM_CELL: u8 = 8;
+pub const GUNPOWDER_CELL: u8 = 9;
+pub const SOURCE_CELL: u8 = 10;
+pub const VOID_CELL: u8 = 11;
+pub const CLONE_CELL: u8 = 12;
+pub const WIRE_CELL: u8 = 13;
+pub const BATTERY_CELL: u8 = 14;
+pub const HYDROGEN_CELL: u8 = 15;
+pub const OXYGEN_CELL: u8 = 16;
+pub const SALT_CELL: u8 = 17;
+pub const SALT_WATER_CELL: u8 = 18;
+pub const CO2_CELL: u8 = 19;
+pub const HONEY_CELL: u8 = 20;
+pub const OIL_CELL: u8 = 21;
+pub const LAVA_CELL: u8 = 22;
+// How many element types there are, every element type is below this
+pub const ELEMENT_COUNT: u8 = 23;
+
+// Density of empty cells, gases lighter than this rise and heavier ones sink
+pub const AIR_DENSITY: f32 = 1.0;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Cell {
@@ -16,15 +35,54 @@ pub struct Cell {
     pub cell_type: u8,
     pub max_life_time: u64,
     pub life_time: u64,
+    pub velocity_x: f32,
+    pub velocity_y: f32,
+    // Element that sources and clones make, empty if it doesn't make anything
+    pub spawn_type: u8,
+    // Powder that stopped sliding, it stays put until something next to it moves
+    pub resting: bool,
 }
 
 impl Cell {
+    // Makes a new cell of any element type
+    pub fn from_type(cell_type: u8) -> Self {
+        match cell_type {
+            SAND_CELL => Cell::new_sand(),
+            STEEL_CELL => Cell::new_steel(),
+            WATER_CELL => Cell::new_water(),
+            WET_SAND_CELL => Cell::new_wet_sand(),
+            FIRE_CELL => Cell::new_fire(),
+            GLASS_CELL => Cell::new_glass(),
+            SMOKE_CELL => Cell::new_smoke(),
+            STEAM_CELL => Cell::new_steam(),
+            GUNPOWDER_CELL => Cell::new_gunpowder(),
+            SOURCE_CELL => Cell::new_source(WATER_CELL),
+            VOID_CELL => Cell::new_void(),
+            CLONE_CELL => Cell::new_clone(),
+            WIRE_CELL => Cell::new_wire(),
+            BATTERY_CELL => Cell::new_battery(),
+            HYDROGEN_CELL => Cell::new_hydrogen(),
+            OXYGEN_CELL => Cell::new_oxygen(),
+            SALT_CELL => Cell::new_salt(),
+            SALT_WATER_CELL => Cell::new_salt_water(),
+            CO2_CELL => Cell::new_co2(),
+            HONEY_CELL => Cell::new_honey(),
+            OIL_CELL => Cell::new_oil(),
+            LAVA_CELL => Cell::new_lava(),
+            _ => Cell::new_empty(),
+        }
+    }
+
     pub fn new_empty() -> Self {
         Self {
             cell_color: WHITE_COLOR,
             cell_type: EMPTY_CELL,
             max_life_time: 0,
             life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
         }
     }
 
@@ -34,6 +92,10 @@ impl Cell {
             cell_type: SAND_CELL,
             max_life_time: 0,
             life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
         }
     }
 
@@ -43,6 +105,10 @@ impl Cell {
             cell_type: STEEL_CELL,
             max_life_time: 0,
             life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
         }
     }
 
@@ -52,6 +118,10 @@ impl Cell {
             cell_type: WATER_CELL,
             max_life_time: 0,
             life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
         }
     }
 
@@ -61,6 +131,10 @@ impl Cell {
             cell_type: WET_SAND_CELL,
             max_life_time: 0,
             life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
         }
     }
 
@@ -70,6 +144,10 @@ impl Cell {
             cell_type: FIRE_CELL,
             max_life_time: 30,
             life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
         }
     }
 
@@ -79,6 +157,10 @@ impl Cell {
             cell_type: GLASS_CELL,
             max_life_time: 30,
             life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
         }
     }
 
@@ -88,6 +170,10 @@ impl Cell {
             cell_type: SMOKE_CELL,
             max_life_time: 400,
             life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
         }
     }
 
@@ -97,6 +183,446 @@ impl Cell {
             cell_type: STEAM_CELL,
             max_life_time: 600,
             life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
+        }
+    }
+
+    pub fn new_gunpowder() -> Self {
+        Self {
+            cell_color: random_color(GUNPOWDER_COLOR),
+            cell_type: GUNPOWDER_CELL,
+            max_life_time: 0,
+            life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
+        }
+    }
+
+    // Source that keeps making the giving element
+    pub fn new_source(spawn_type: u8) -> Self {
+        Self {
+            cell_color: random_color(SOURCE_COLOR),
+            cell_type: SOURCE_CELL,
+            max_life_time: 0,
+            life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type,
+            resting: false,
+        }
+    }
+
+    pub fn new_void() -> Self {
+        Self {
+            cell_color: random_color(VOID_COLOR),
+            cell_type: VOID_CELL,
+            max_life_time: 0,
+            life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
+        }
+    }
+
+    // Clones start empty and pick up the first element that touches them
+    pub fn new_clone() -> Self {
+        Self {
+            cell_color: random_color(CLONE_COLOR),
+            cell_type: CLONE_CELL,
+            max_life_time: 0,
+            life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
+        }
+    }
+
+    pub fn new_wire() -> Self {
+        Self {
+            cell_color: random_color(WIRE_COLOR),
+            cell_type: WIRE_CELL,
+            max_life_time: 0,
+            life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
+        }
+    }
+
+    pub fn new_battery() -> Self {
+        Self {
+            cell_color: random_color(BATTERY_COLOR),
+            cell_type: BATTERY_CELL,
+            max_life_time: 0,
+            life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
+        }
+    }
+
+    pub fn new_hydrogen() -> Self {
+        Self {
+            cell_color: random_color(HYDROGEN_COLOR),
+            cell_type: HYDROGEN_CELL,
+            max_life_time: 0,
+            life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
+        }
+    }
+
+    pub fn new_oxygen() -> Self {
+        Self {
+            cell_color: random_color(OXYGEN_COLOR),
+            cell_type: OXYGEN_CELL,
+            max_life_time: 0,
+            life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
+        }
+    }
+
+    pub fn new_salt() -> Self {
+        Self {
+            cell_color: random_color(SALT_COLOR),
+            cell_type: SALT_CELL,
+            max_life_time: 0,
+            life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
+        }
+    }
+
+    pub fn new_salt_water() -> Self {
+        Self {
+            cell_color: random_color(SALT_WATER_COLOR),
+            cell_type: SALT_WATER_CELL,
+            max_life_time: 0,
+            life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
+        }
+    }
+
+    pub fn new_co2() -> Self {
+        Self {
+            cell_color: random_color(CO2_COLOR),
+            cell_type: CO2_CELL,
+            max_life_time: 0,
+            life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
+        }
+    }
+
+    pub fn new_honey() -> Self {
+        Self {
+            cell_color: random_color(HONEY_COLOR),
+            cell_type: HONEY_CELL,
+            max_life_time: 0,
+            life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
+        }
+    }
+
+    pub fn new_oil() -> Self {
+        Self {
+            cell_color: random_color(OIL_COLOR),
+            cell_type: OIL_CELL,
+            max_life_time: 0,
+            life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
         }
     }
+
+    pub fn new_lava() -> Self {
+        Self {
+            cell_color: random_color(LAVA_COLOR),
+            cell_type: LAVA_CELL,
+            max_life_time: 0,
+            life_time: 0,
+            velocity_x: 0.0,
+            velocity_y: 0.0,
+            spawn_type: EMPTY_CELL,
+            resting: false,
+        }
+    }
+}
+
+// Display name of a element
+pub fn element_name(cell_type: u8) -> &'static str {
+    match cell_type {
+        EMPTY_CELL => "Eraser",
+        SAND_CELL => "Sand",
+        STEEL_CELL => "Steel",
+        WATER_CELL => "Water",
+        WET_SAND_CELL => "Wet Sand",
+        FIRE_CELL => "Fire",
+        GLASS_CELL => "Glass",
+        SMOKE_CELL => "Smoke",
+        STEAM_CELL => "Steam",
+        GUNPOWDER_CELL => "Gunpowder",
+        SOURCE_CELL => "Source",
+        VOID_CELL => "Void",
+        CLONE_CELL => "Clone",
+        WIRE_CELL => "Wire",
+        BATTERY_CELL => "Battery",
+        HYDROGEN_CELL => "Hydrogen",
+        OXYGEN_CELL => "Oxygen",
+        SALT_CELL => "Salt",
+        SALT_WATER_CELL => "Salt Water",
+        CO2_CELL => "CO2",
+        HONEY_CELL => "Honey",
+        OIL_CELL => "Oil",
+        LAVA_CELL => "Lava",
+        _ => "<element>",
+    }
+}
+
+// Element with the giving name, the name doesn't care about upper or lower case
+// and "empty" also means the eraser
+pub fn element_from_name(name: &str) -> Option<u8> {
+    if name.eq_ignore_ascii_case("empty") {
+        return Some(EMPTY_CELL);
+    }
+    (0..ELEMENT_COUNT).find(|&cell_type| element_name(cell_type).eq_ignore_ascii_case(name))
+}
+
+// Character a element is drawn with in ASCII scenes, like S for sand and # for steel
+pub fn element_symbol(cell_type: u8) -> char {
+    match cell_type {
+        EMPTY_CELL => '.',
+        SAND_CELL => 'S',
+        STEEL_CELL => '#',
+        WATER_CELL => 'W',
+        WET_SAND_CELL => 's',
+        FIRE_CELL => 'F',
+        GLASS_CELL => 'G',
+        SMOKE_CELL => 'M',
+        STEAM_CELL => 'T',
+        GUNPOWDER_CELL => 'P',
+        SOURCE_CELL => '*',
+        VOID_CELL => 'V',
+        CLONE_CELL => 'C',
+        WIRE_CELL => '-',
+        BATTERY_CELL => 'B',
+        HYDROGEN_CELL => 'H',
+        OXYGEN_CELL => 'O',
+        SALT_CELL => 'N',
+        SALT_WATER_CELL => 'n',
+        CO2_CELL => 'D',
+        HONEY_CELL => 'Y',
+        OIL_CELL => 'o',
+        LAVA_CELL => 'L',
+        _ => '?',
+    }
+}
+
+// Element drawn with the giving character in ASCII scenes
+pub fn element_from_symbol(symbol: char) -> Option<u8> {
+    (0..ELEMENT_COUNT).find(|&cell_type| element_symbol(cell_type) == symbol)
+}
+
+// Base color of a element, used for previews and the element buttons
+pub fn element_color(cell_type: u8) -> [f32; 4] {
+    match cell_type {
+        EMPTY_CELL => LIGHT_BLUE_COLOR,
+        SAND_CELL => SAND_COLOR,
+        STEEL_CELL => STEEL_COLOR,
+        WATER_CELL => WATER_COLOR,
+        WET_SAND_CELL => WET_SAND_COLOR,
+        FIRE_CELL => FIRE_COLOR,
+        GLASS_CELL => GLASS_COLOR,
+        SMOKE_CELL => SMOKE_COLOR,
+        STEAM_CELL => STEAM_COLOR,
+        GUNPOWDER_CELL => GUNPOWDER_COLOR,
+        SOURCE_CELL => SOURCE_COLOR,
+        VOID_CELL => VOID_COLOR,
+        CLONE_CELL => CLONE_COLOR,
+        WIRE_CELL => WIRE_COLOR,
+        BATTERY_CELL => BATTERY_COLOR,
+        HYDROGEN_CELL => HYDROGEN_COLOR,
+        OXYGEN_CELL => OXYGEN_COLOR,
+        SALT_CELL => SALT_COLOR,
+        SALT_WATER_CELL => SALT_WATER_COLOR,
+        CO2_CELL => CO2_COLOR,
+        HONEY_CELL => HONEY_COLOR,
+        OIL_CELL => OIL_COLOR,
+        LAVA_CELL => LAVA_COLOR,
+        _ => TRANSPAERNT_COLOR,
+    }
+}
+
+// How strong a blast has to be at a cell before it gets destroyed
+// Steel is never destroyed by a blast
+pub fn blast_resistance(cell_type: u8) -> f32 {
+    match cell_type {
+        STEEL_CELL | SOURCE_CELL | VOID_CELL | CLONE_CELL => f32::INFINITY,
+        WIRE_CELL | BATTERY_CELL => 3.0,
+        GLASS_CELL => 2.0,
+        WET_SAND_CELL => 1.5,
+        SAND_CELL | SALT_CELL => 1.0,
+        WATER_CELL | SALT_WATER_CELL | HONEY_CELL | OIL_CELL | LAVA_CELL => 0.5,
+        _ => 0.0,
+    }
+}
+
+// If a cell gets thrown around by a blast instead of staying in place
+pub fn is_debris(cell_type: u8) -> bool {
+    matches!(
+        cell_type,
+        SAND_CELL
+            | WATER_CELL
+            | WET_SAND_CELL
+            | GUNPOWDER_CELL
+            | SALT_CELL
+            | SALT_WATER_CELL
+            | HONEY_CELL
+            | OIL_CELL
+            | LAVA_CELL
+    )
+}
+
+// If a cell is part of a fixed structure that never moves on its own
+pub fn is_solid(cell_type: u8) -> bool {
+    matches!(
+        cell_type,
+        STEEL_CELL | GLASS_CELL | SOURCE_CELL | VOID_CELL | CLONE_CELL | WIRE_CELL | BATTERY_CELL
+    )
+}
+
+// If a cell is part of the structures that fall as one piece when rigid bodies are on
+pub fn is_rigid(cell_type: u8) -> bool {
+    matches!(
+        cell_type,
+        STEEL_CELL | GLASS_CELL | WIRE_CELL | BATTERY_CELL
+    )
+}
+
+// How easily the wind pushes a element around, 0.0 means it never moves with the wind
+pub fn wind_susceptibility(cell_type: u8) -> f32 {
+    match cell_type {
+        FIRE_CELL => 0.8,
+        SALT_CELL | GUNPOWDER_CELL => 0.4,
+        SAND_CELL => 0.15,
+        cell_type if is_gas(cell_type) => 1.0,
+        _ => 0.0,
+    }
+}
+
+// If a cell gets pushed out of the way by a falling rigid body
+pub fn is_displaceable(cell_type: u8) -> bool {
+    cell_type == EMPTY_CELL || cell_type == FIRE_CELL || is_liquid(cell_type) || is_gas(cell_type)
+}
+
+// If electricity can travel through a cell
+pub fn is_conductor(cell_type: u8) -> bool {
+    matches!(cell_type, STEEL_CELL | WIRE_CELL)
+}
+
+// If a spark or fire sets a cell on fire
+pub fn is_flammable(cell_type: u8) -> bool {
+    matches!(cell_type, GUNPOWDER_CELL | HYDROGEN_CELL | OIL_CELL)
+}
+
+// If a cell flows like a liquid
+pub fn is_liquid(cell_type: u8) -> bool {
+    matches!(
+        cell_type,
+        WATER_CELL | SALT_WATER_CELL | HONEY_CELL | OIL_CELL | LAVA_CELL
+    )
+}
+
+// How a powder piles up
+pub struct PowderProperties {
+    // Chance every tick that the powder stops sliding down a slope and rests,
+    // higher friction makes steeper piles
+    pub friction: f32,
+}
+
+pub fn powder_properties(cell_type: u8) -> PowderProperties {
+    let friction = match cell_type {
+        SAND_CELL => 0.35,
+        WET_SAND_CELL => 0.85,
+        SALT_CELL => 0.2,
+        GUNPOWDER_CELL => 0.05,
+        _ => 0.5,
+    };
+
+    PowderProperties { friction }
+}
+
+// How a liquid flows
+pub struct LiquidProperties {
+    // Chance every tick that the liquid is too thick to spread sideways
+    pub viscosity: f32,
+    // Most cells the liquid can spread sideways in one tick
+    pub flow_distance: i64,
+}
+
+pub fn liquid_properties(cell_type: u8) -> LiquidProperties {
+    let (viscosity, flow_distance) = match cell_type {
+        WATER_CELL | SALT_WATER_CELL => (0.0, 3),
+        OIL_CELL => (0.2, 2),
+        LAVA_CELL => (0.7, 1),
+        HONEY_CELL => (0.85, 1),
+        _ => (0.0, 1),
+    };
+
+    LiquidProperties {
+        viscosity,
+        flow_distance,
+    }
+}
+
+// If a cell floats around like a gas
+pub fn is_gas(cell_type: u8) -> bool {
+    matches!(
+        cell_type,
+        SMOKE_CELL | STEAM_CELL | HYDROGEN_CELL | OXYGEN_CELL | CO2_CELL
+    )
+}
+
+// How heavy a element is, heavier liquids sink below lighter ones
+// Gases are compared to AIR_DENSITY
+pub fn density(cell_type: u8) -> f32 {
+    match cell_type {
+        WATER_CELL => 1.0,
+        SALT_WATER_CELL => 1.2,
+        OIL_CELL => 0.8,
+        HONEY_CELL => 1.4,
+        LAVA_CELL => 2.5,
+        HYDROGEN_CELL => 0.1,
+        STEAM_CELL => 0.6,
+        SMOKE_CELL => 0.8,
+        OXYGEN_CELL => 1.1,
+        CO2_CELL => 1.5,
+        _ => 0.0,
+    }
 }
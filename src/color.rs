@@ -11,6 +11,39 @@ pub const FIRE_COLOR: [f32; 4] = [1.0, 0.28, 0.0, 1.0];
 pub const GLASS_COLOR: [f32; 4] = [1.0, 0.98, 0.96, 1.0];
 pub const SMOKE_COLOR: [f32; 4] = [0.24, 0.22, 0.22, 1.0];
 pub const STEAM_COLOR: [f32; 4] = [0.82, 0.82, 0.87, 1.0];
+pub const GUNPOWDER_COLOR: [f32; 4] = [0.3, 0.3, 0.32, 1.0];
+pub const SOURCE_COLOR: [f32; 4] = [0.1, 0.6, 0.3, 1.0];
+pub const VOID_COLOR: [f32; 4] = [0.05, 0.0, 0.1, 1.0];
+pub const CLONE_COLOR: [f32; 4] = [0.7, 0.6, 0.1, 1.0];
+pub const WIRE_COLOR: [f32; 4] = [0.72, 0.45, 0.2, 1.0];
+pub const BATTERY_COLOR: [f32; 4] = [0.15, 0.5, 0.15, 1.0];
+pub const HYDROGEN_COLOR: [f32; 4] = [0.85, 0.9, 1.0, 1.0];
+pub const OXYGEN_COLOR: [f32; 4] = [0.7, 0.85, 0.95, 1.0];
+pub const SALT_COLOR: [f32; 4] = [0.95, 0.95, 0.93, 1.0];
+pub const SALT_WATER_COLOR: [f32; 4] = [0.15, 0.35, 0.85, 1.0];
+pub const CO2_COLOR: [f32; 4] = [0.55, 0.6, 0.55, 1.0];
+pub const HONEY_COLOR: [f32; 4] = [0.95, 0.68, 0.1, 1.0];
+pub const OIL_COLOR: [f32; 4] = [0.25, 0.18, 0.08, 1.0];
+pub const LAVA_COLOR: [f32; 4] = [1.0, 0.35, 0.05, 1.0];
+pub const SPARK_COLOR: [f32; 4] = [1.0, 0.95, 0.4, 1.0];
+pub const FORCE_FIELD_COLOR: [f32; 4] = [1.0, 1.0, 1.0, 0.25];
+pub const HELP_BACKGROUND_COLOR: [f32; 4] = [0.05, 0.05, 0.1, 0.95];
+pub const PROCESSED_OVERLAY_COLOR: [f32; 4] = [1.0, 1.0, 0.0, 0.35];
+pub const CHANGED_CHUNK_COLOR: [f32; 4] = [1.0, 0.1, 0.1, 0.8];
+
+// Color of a value between 0 and 1 in a heat map, it goes from blue over
+// cyan, green and yellow to red
+pub fn false_color(value: f32) -> [f32; 4] {
+    let value = value.clamp(0.0, 1.0) * 4.0;
+    let part = value.fract();
+    match value as u32 {
+        0 => [0.0, part, 1.0, 1.0],
+        1 => [0.0, 1.0, 1.0 - part, 1.0],
+        2 => [part, 1.0, 0.0, 1.0],
+        3 => [1.0, 1.0 - part, 0.0, 1.0],
+        _ => [1.0, 0.0, 0.0, 1.0],
+    }
+}
 
 pub fn random_color(base_color: [f32; 4]) -> [f32; 4] {
     let mut rng = rand::rng();
@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use particle_sim::cell::element_from_name;
+use particle_sim::grid::{MAX_GRAVITY_STRENGTH, MAX_WORLD_SIZE};
+use serde::Deserialize;
+
+use crate::PANEL_WIDTH;
+use crate::ui::camera::{MAX_ZOOM, MIN_ZOOM};
+use crate::ui::input::Bindings;
+
+const USAGE: &str = "Usage: particle-sim [options]
+
+Options:
+    --config PATH       Config file to use instead of the one in the config dir
+    --size WxH          Size of the world in cells, like 340x260, at most 2000x2000
+    --cell-size PIXELS  How big a cell is drawn
+    --interval MS       Milliseconds between simulation steps
+    --window WxH        Size of the window in pixels, like 1390x910
+    --element NAME      Element that is picked at the start, like sand
+    --seed NUMBER       Seed for the simulation, so runs repeat exactly
+    --gravity STRENGTH  How hard gravity pulls, 1 is normal, between 0 and 4
+    --world PATH        World file to load at the start
+    --help              Show this message";
+
+// Settings as they are written in the config file, every one of them can be left out
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    grid_width: i64,
+    grid_height: i64,
+    cell_size: f64,
+    update_interval_ms: u64,
+    window_width: u32,
+    window_height: u32,
+    element: String,
+    seed: Option<u64>,
+    // Left out it is 1, or what the world file says
+    gravity_strength: Option<f32>,
+    world: Option<PathBuf>,
+    // Action name to key name, like save_world = "F5"
+    keys: HashMap<String, String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            grid_width: 340,
+            grid_height: 260,
+            cell_size: 7.0,
+            update_interval_ms: 5,
+            window_width: 1390,
+            window_height: 910,
+            element: "sand".to_string(),
+            seed: None,
+            gravity_strength: None,
+            world: None,
+            keys: HashMap::new(),
+        }
+    }
+}
+
+// Checked settings the sim starts with
+pub struct Settings {
+    pub grid_size: (i64, i64),
+    pub cell_size: f64,
+    pub update_interval: Duration,
+    pub window_size: (u32, u32),
+    pub element: u8,
+    pub seed: Option<u64>,
+    pub gravity_strength: Option<f32>,
+    pub world: Option<PathBuf>,
+    pub bindings: Bindings,
+}
+
+// Reads the settings from the config file and the command line, the command line wins
+//
+// The config file is config.toml in the particle-sim folder of the user's config dir,
+// it's fine if it's not there. Returns Ok(None) if the help was asked for
+pub fn load_settings() -> Result<Option<Settings>, String> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{}", USAGE);
+        return Ok(None);
+    }
+
+    // The config file has to be read first so the flags can go on top of it
+    let options = parse_args(&args)?;
+    let config_path = options
+        .iter()
+        .rev()
+        .find(|(flag, _)| *flag == "--config")
+        .map(|(_, path)| path);
+    let mut config = match config_path {
+        Some(path) => read_config(Path::new(path))?,
+        None => match default_config_path() {
+            Some(path) if path.exists() => read_config(&path)?,
+            _ => Config::default(),
+        },
+    };
+
+    for (flag, value) in &options {
+        match *flag {
+            "--config" => {}
+            "--size" => (config.grid_width, config.grid_height) = parse_size(flag, value)?,
+            "--cell-size" => config.cell_size = parse_number(flag, value)?,
+            "--interval" => config.update_interval_ms = parse_number(flag, value)?,
+            "--window" => (config.window_width, config.window_height) = parse_size(flag, value)?,
+            "--element" => config.element = value.clone(),
+            "--seed" => config.seed = Some(parse_number(flag, value)?),
+            "--gravity" => config.gravity_strength = Some(parse_number(flag, value)?),
+            "--world" => config.world = Some(PathBuf::from(value)),
+            _ => unreachable!("parse_args only returns known flags"),
+        }
+    }
+
+    check(config).map(Some)
+}
+
+// Where the config file is looked for when --config isn't giving
+pub fn default_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("particle-sim").join("config.toml"))
+}
+
+fn read_config(path: &Path) -> Result<Config, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|error| format!("could not read {}: {}", path.display(), error))?;
+    toml::from_str(&text).map_err(|error| format!("bad config {}: {}", path.display(), error))
+}
+
+// Makes sure every setting makes sense and turns them into the settings the sim uses
+fn check(config: Config) -> Result<Settings, String> {
+    if config.grid_width <= 0 || config.grid_height <= 0 {
+        return Err(format!(
+            "the world size has to be bigger than 0, it is {}x{}",
+            config.grid_width, config.grid_height
+        ));
+    }
+    if config.grid_width > MAX_WORLD_SIZE || config.grid_height > MAX_WORLD_SIZE {
+        return Err(format!(
+            "the world can be at most {}x{} cells, it is {}x{}",
+            MAX_WORLD_SIZE, MAX_WORLD_SIZE, config.grid_width, config.grid_height
+        ));
+    }
+    if !(MIN_ZOOM..=MAX_ZOOM).contains(&config.cell_size) {
+        return Err(format!(
+            "cell_size has to be between {} and {}, it is {}",
+            MIN_ZOOM, MAX_ZOOM, config.cell_size
+        ));
+    }
+    if (config.window_width as f64) <= PANEL_WIDTH || config.window_height == 0 {
+        return Err(format!(
+            "the window has to be wider than the {} pixel side panel and taller than 0, it is {}x{}",
+            PANEL_WIDTH, config.window_width, config.window_height
+        ));
+    }
+    if let Some(strength) = config.gravity_strength
+        && !(0.0..=MAX_GRAVITY_STRENGTH).contains(&strength)
+    {
+        return Err(format!(
+            "gravity_strength has to be between 0 and {}, it is {}",
+            MAX_GRAVITY_STRENGTH, strength
+        ));
+    }
+    let element = element_from_name(&config.element)
+        .ok_or_else(|| format!("unknown element \"{}\"", config.element))?;
+    let bindings = Bindings::from_config(&config.keys)?;
+
+    Ok(Settings {
+        grid_size: (config.grid_width, config.grid_height),
+        cell_size: config.cell_size,
+        update_interval: Duration::from_millis(config.update_interval_ms),
+        window_size: (config.window_width, config.window_height),
+        element,
+        seed: config.seed,
+        gravity_strength: config.gravity_strength,
+        world: config.world,
+        bindings,
+    })
+}
+
+// Pairs every flag with its value
+fn parse_args(args: &[String]) -> Result<Vec<(&'static str, String)>, String> {
+    const FLAGS: [&str; 9] = [
+        "--config",
+        "--size",
+        "--cell-size",
+        "--interval",
+        "--window",
+        "--element",
+        "--seed",
+        "--gravity",
+        "--world",
+    ];
+
+    let mut options = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let flag = FLAGS
+            .iter()
+            .find(|&&flag| flag == arg)
+            .ok_or_else(|| format!("unknown argument \"{}\", see --help", arg))?;
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{} needs a value, see --help", flag))?;
+        options.push((*flag, value.clone()));
+    }
+
+    Ok(options)
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} needs a number, \"{}\" isn't one", flag, value))
+}
+
+// Parses a size like 340x260
+fn parse_size<T>(flag: &str, value: &str) -> Result<(T, T), String>
+where
+    T: std::str::FromStr + Default + PartialOrd,
+{
+    let error = || {
+        format!(
+            "{} needs a size like 340x260, \"{}\" isn't one",
+            flag, value
+        )
+    };
+    let (width, height) = value.split_once('x').ok_or_else(error)?;
+    let width: T = width.parse().map_err(|_| error())?;
+    let height: T = height.parse().map_err(|_| error())?;
+    if width <= T::default() || height <= T::default() {
+        return Err(format!("{} has to be bigger than 0, it is {}", flag, value));
+    }
+    Ok((width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn flags_are_paired_with_their_values() {
+        let options = parse_args(&args(&["--size", "100x50", "--seed", "3"])).unwrap();
+        assert_eq!(
+            options,
+            [
+                ("--size", "100x50".to_string()),
+                ("--seed", "3".to_string())
+            ]
+        );
+
+        assert_eq!(
+            parse_args(&args(&["--sizes", "100x50"])),
+            Err("unknown argument \"--sizes\", see --help".to_string())
+        );
+        assert_eq!(
+            parse_args(&args(&["--seed"])),
+            Err("--seed needs a value, see --help".to_string())
+        );
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(parse_size::<i64>("--size", "340x260"), Ok((340, 260)));
+        assert!(parse_size::<i64>("--size", "340").is_err());
+        assert!(parse_size::<i64>("--size", "340xtall").is_err());
+        assert!(parse_size::<i64>("--size", "0x260").is_err());
+        assert!(parse_size::<i64>("--size", "-5x260").is_err());
+
+        // Window sizes are parsed as u32 so they can't wrap around
+        assert_eq!(parse_size::<u32>("--window", "1390x910"), Ok((1390, 910)));
+        assert!(parse_size::<u32>("--window", "4294967297x910").is_err());
+        assert!(parse_size::<u32>("--window", "-1x910").is_err());
+    }
+
+    #[test]
+    fn default_config_is_fine() {
+        let settings = check(Config::default()).unwrap();
+        assert_eq!(settings.grid_size, (340, 260));
+        assert_eq!(settings.window_size, (1390, 910));
+    }
+
+    #[test]
+    fn bad_settings_are_errors() {
+        let configs = [
+            Config {
+                grid_width: 0,
+                ..Config::default()
+            },
+            Config {
+                grid_height: MAX_WORLD_SIZE + 1,
+                ..Config::default()
+            },
+            Config {
+                cell_size: MAX_ZOOM * 2.0,
+                ..Config::default()
+            },
+            Config {
+                window_width: PANEL_WIDTH as u32,
+                ..Config::default()
+            },
+            Config {
+                element: "unobtainium".to_string(),
+                ..Config::default()
+            },
+            Config {
+                gravity_strength: Some(-0.5),
+                ..Config::default()
+            },
+            Config {
+                gravity_strength: Some(MAX_GRAVITY_STRENGTH + 1.0),
+                ..Config::default()
+            },
+        ];
+
+        for config in configs {
+            assert!(check(config).is_err());
+        }
+    }
+
+    #[test]
+    fn huge_worlds_get_a_clear_error() {
+        let config = Config {
+            grid_width: 100_000,
+            grid_height: 100_000,
+            ..Config::default()
+        };
+        match check(config) {
+            Ok(_) => panic!("a 100000x100000 world was allowed"),
+            Err(error) => assert_eq!(
+                error,
+                "the world can be at most 2000x2000 cells, it is 100000x100000"
+            ),
+        }
+    }
+}
@@ -1,13 +1,87 @@
 use crate::cell::*;
-use rand;
+use crate::tools::line_positions;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use std::io::{self, Write};
+use rand::{Rng, SeedableRng};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 
+// How big the blast of a single gunpowder particle is
+const GUNPOWDER_BLAST_POWER: f32 = 4.0;
+// Pull of gravity on particles that are flying through the air
+const FLYING_GRAVITY: f32 = 0.4;
+// Strongest gravity can be, see Grid::gravity_strength
+pub const MAX_GRAVITY_STRENGTH: f32 = 4.0;
+// Fastest a flying particle can move in a tick
+pub const MAX_VELOCITY: f32 = 6.0;
+// How big the blast is when fire reaches hydrogen
+const HYDROGEN_BLAST_POWER: f32 = 2.0;
+
+// Most liquid cells a connected body of liquid can push up per tick
+const PRESSURE_MOVES_PER_TICK: usize = 2;
+
+// The 8 directions going clockwise, used to turn directions like gravity
+const DIRECTIONS: [(i64, i64); 8] = [
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+];
+
+// How often two gases next to each other swap places
+const GAS_MIX_CHANCE: f64 = 0.3;
+
+// Charge of a conductor that was just hit by a spark, it counts down to 0 every tick
+// Anything between 0 and this is the refractory period where it can't be charged again
+pub const CHARGE_HEAD: u8 = 3;
+// Width and height of the regions changes are tracked in, in cells
+pub const CHUNK_SIZE: i64 = 8;
+// Biggest a side of the world can be, a bigger world takes too much memory
+pub const MAX_WORLD_SIZE: i64 = 2000;
+
+// First line of a ASCII world, see Grid::to_ascii
+pub const ASCII_HEADER: &str = "particle-sim ascii 1";
+
+// How many ticks between each pulse a battery sends out
+const BATTERY_PULSE_TICKS: u64 = 20;
+
+#[derive(Clone)]
 pub struct Grid {
     pub width: i64,
     pub height: i64,
     pub grid: Vec<Vec<Cell>>,
+    // Electric charge of every cell, see CHARGE_HEAD
+    pub charge: Vec<Vec<u8>>,
+    pub tick: u64,
+    // If connected solids fall as one piece when nothing is holding them up
+    pub rigid_bodies: bool,
+    // Wind that pushes on every cell in the world
+    pub wind: (f32, f32),
+    // Extra push painted onto parts of the world, added on top of the wind
+    pub force_field: Vec<Vec<(f32, f32)>>,
+    // Direction things fall in, one of the 8 directions or (0, 0) for no gravity
+    pub gravity: (i64, i64),
+    // How hard gravity pulls, 1 is normal and 0 is the same as no gravity
+    // Below 1 things only fall some of the ticks, flying things get pulled harder the
+    // stronger it is
+    pub gravity_strength: f32,
+    // What happens to particles that move past each edge of the grid
+    pub boundaries: Boundaries,
     processed: Vec<Vec<bool>>,
+    // Chunks where a cell turned into something else last tick, see CHUNK_SIZE
+    changed_chunks: Vec<Vec<bool>>,
+    // How long the last update took
+    update_time: Duration,
+    // Random numbers for the rules, seed it to make runs repeat exactly
+    rng: StdRng,
+    // Seed the rng was last seeded with, None if it is random
+    seed: Option<u64>,
 }
 
 impl Grid {
@@ -17,10 +91,34 @@ impl Grid {
             width,
             height,
             grid: Self::make_grid(width, height),
+            charge: vec![vec![0; width as usize]; height as usize],
+            tick: 0,
+            rigid_bodies: false,
+            wind: (0.0, 0.0),
+            force_field: vec![vec![(0.0, 0.0); width as usize]; height as usize],
+            gravity: (0, 1),
+            gravity_strength: 1.0,
+            boundaries: Boundaries::default(),
             processed: vec![vec![false; width as usize]; height as usize],
+            changed_chunks: Self::make_chunks(width, height),
+            update_time: Duration::ZERO,
+            rng: StdRng::from_rng(&mut rand::rng()),
+            seed: None,
         }
     }
 
+    // Makes the rules use random numbers from the giving seed, so the same world
+    // does the same thing every time
+    pub fn seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.seed = Some(seed);
+    }
+
+    // Seed the rules were last seeded with, None if they use random numbers
+    pub fn rng_seed(&self) -> Option<u64> {
+        self.seed
+    }
+
     // Retunes a grid
     fn make_grid(size_x: i64, size_y: i64) -> Vec<Vec<Cell>> {
         let mut grid: Vec<Vec<Cell>> = Vec::new();
@@ -36,6 +134,65 @@ impl Grid {
         grid
     }
 
+    // Changes the size of the grid and keeps what is in it
+    // The anchor decides which side of the old world stays put, anything that doesn't fit
+    // in the new size is cut off
+    pub fn resize(&mut self, new_width: i64, new_height: i64, anchor: Anchor) {
+        assert!(
+            new_width > 0 && new_height > 0,
+            "grid size has to be bigger than 0"
+        );
+
+        let (offset_x, offset_y) =
+            anchor.offset((self.width, self.height), (new_width, new_height));
+        let mut resized = Grid::new(new_width, new_height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (nx, ny) = (x + offset_x, y + offset_y);
+                if nx < 0 || ny < 0 || nx >= new_width || ny >= new_height {
+                    continue;
+                }
+                let (x, y, nx, ny) = (x as usize, y as usize, nx as usize, ny as usize);
+                resized.grid[ny][nx] = self.grid[y][x];
+                resized.charge[ny][nx] = self.charge[y][x];
+                resized.force_field[ny][nx] = self.force_field[y][x];
+            }
+        }
+
+        self.width = new_width;
+        self.height = new_height;
+        self.grid = resized.grid;
+        self.charge = resized.charge;
+        self.force_field = resized.force_field;
+        self.processed = resized.processed;
+        self.changed_chunks = resized.changed_chunks;
+    }
+
+    fn make_chunks(width: i64, height: i64) -> Vec<Vec<bool>> {
+        let chunks_x = (width + CHUNK_SIZE - 1) / CHUNK_SIZE;
+        let chunks_y = (height + CHUNK_SIZE - 1) / CHUNK_SIZE;
+        vec![vec![false; chunks_x as usize]; chunks_y as usize]
+    }
+
+    // If the cell at the giving cords was already moved or updated this tick
+    pub fn is_processed(&self, x: i64, y: i64) -> bool {
+        self.processed[y as usize][x as usize]
+    }
+
+    // Chunks where any cell turned into a different element last tick, as chunk cords
+    // The cells of a chunk start at its cords times CHUNK_SIZE
+    pub fn changed_chunks(&self) -> Vec<(i64, i64)> {
+        let mut chunks = Vec::new();
+        for (chunk_y, row) in self.changed_chunks.iter().enumerate() {
+            for (chunk_x, &changed) in row.iter().enumerate() {
+                if changed {
+                    chunks.push((chunk_x as i64, chunk_y as i64));
+                }
+            }
+        }
+        chunks
+    }
+
     // Places a element in a circle based of the cords you want
     pub fn place_element(&mut self, x: i32, y: i32, selected_element: u8, brush_size: i32) {
         let positions = self.get_circle_positions(x, y, brush_size);
@@ -45,17 +202,113 @@ impl Grid {
                 continue;
             }
 
-            self.grid[yp as usize][xp as usize] = match selected_element {
-                SAND_CELL => Cell::new_sand(),
-                STEEL_CELL => Cell::new_steel(),
-                WATER_CELL => Cell::new_water(),
-                FIRE_CELL => Cell::new_fire(),
-                EMPTY_CELL => Cell::new_empty(),
-                _ => Cell::new_empty(),
+            self.grid[yp as usize][xp as usize] = Cell::from_type(selected_element);
+        }
+    }
+
+    // Places a source in a circle that keeps making the giving element
+    pub fn place_source(&mut self, x: i32, y: i32, spawn_type: u8, brush_size: i32) {
+        let positions = self.get_circle_positions(x, y, brush_size);
+
+        for (xp, yp) in positions {
+            if self.grid[yp as usize][xp as usize].cell_type != EMPTY_CELL {
+                continue;
+            }
+
+            self.grid[yp as usize][xp as usize] = Cell::new_source(spawn_type);
+        }
+    }
+
+    // Places a element on the giving positions, sources make the spawn type
+    // It doesn't draw over other elements unless it is erasing or the placement replaces
+    pub fn place_positions(
+        &mut self,
+        positions: &[(i32, i32)],
+        selected_element: u8,
+        spawn_type: u8,
+        placement: Placement,
+    ) {
+        for &(x, y) in positions {
+            if placement.density < 1.0 && !self.rng.random_bool(placement.density.max(0.0)) {
+                continue;
+            }
+
+            let cell = &mut self.grid[y as usize][x as usize];
+            if cell.cell_type != EMPTY_CELL && selected_element != EMPTY_CELL && !placement.replace
+            {
+                continue;
+            }
+
+            *cell = if selected_element == SOURCE_CELL {
+                Cell::new_source(spawn_type)
+            } else {
+                Cell::from_type(selected_element)
             };
         }
     }
 
+    // Turns every cell connected to the giving cords that is the same element into the
+    // selected element, sources make the spawn type
+    pub fn flood_fill(&mut self, x: i32, y: i32, selected_element: u8, spawn_type: u8) {
+        for (fx, fy) in self.flood_fill_positions(x, y) {
+            self.grid[fy as usize][fx as usize] = if selected_element == SOURCE_CELL {
+                Cell::new_source(spawn_type)
+            } else {
+                Cell::from_type(selected_element)
+            };
+        }
+    }
+
+    // Cells connected to the giving cords that are the same element, cells only count
+    // as connected if they touch on a side
+    pub fn flood_fill_positions(&self, x: i32, y: i32) -> Vec<(i32, i32)> {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return Vec::new();
+        }
+
+        let cell_type = self.grid[y as usize][x as usize].cell_type;
+        let mut seen = vec![vec![false; self.width as usize]; self.height as usize];
+        let mut positions = Vec::new();
+        let mut stack = vec![(x, y)];
+        seen[y as usize][x as usize] = true;
+
+        while let Some((cx, cy)) = stack.pop() {
+            positions.push((cx, cy));
+
+            for (nx, ny) in [(cx - 1, cy), (cx + 1, cy), (cx, cy - 1), (cx, cy + 1)] {
+                if nx < 0 || ny < 0 || nx >= self.width as i32 || ny >= self.height as i32 {
+                    continue;
+                }
+                if !seen[ny as usize][nx as usize]
+                    && self.grid[ny as usize][nx as usize].cell_type == cell_type
+                {
+                    seen[ny as usize][nx as usize] = true;
+                    stack.push((nx, ny));
+                }
+            }
+        }
+
+        positions
+    }
+
+    // Paints a force field in a circle that pushes cells in the direction of force
+    pub fn place_force(&mut self, x: i32, y: i32, force: (f32, f32), brush_size: i32) {
+        let positions = self.get_circle_positions(x, y, brush_size);
+
+        for (xp, yp) in positions {
+            self.force_field[yp as usize][xp as usize] = force;
+        }
+    }
+
+    // Removes every painted force field
+    pub fn clear_forces(&mut self) {
+        for row in &mut self.force_field {
+            for force in row {
+                *force = (0.0, 0.0);
+            }
+        }
+    }
+
     // Get all list elements in a circle
     pub fn get_circle_positions(
         &self,
@@ -83,8 +336,133 @@ impl Grid {
         positions
     }
 
+    // Everything there is to know about the cell at the giving cords, one line each,
+    // None if the cords are outside the grid
+    pub fn inspect(&self, x: i32, y: i32) -> Option<Vec<String>> {
+        if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
+            return None;
+        }
+        let cell = &self.grid[y as usize][x as usize];
+        let force = self.force_field[y as usize][x as usize];
+        let name = if cell.cell_type == EMPTY_CELL {
+            "Empty"
+        } else {
+            element_name(cell.cell_type)
+        };
+
+        let mut lines = vec![
+            format!("{} at {}, {}", name, x, y),
+            format!("Type: {}", cell.cell_type),
+            format!("Life: {} / {}", cell.life_time, cell.max_life_time),
+            format!(
+                "Color: {:.2}, {:.2}, {:.2}",
+                cell.cell_color[0], cell.cell_color[1], cell.cell_color[2]
+            ),
+            format!("Velocity: {:.1}, {:.1}", cell.velocity_x, cell.velocity_y),
+        ];
+        if cell.spawn_type != EMPTY_CELL {
+            lines.push(format!("Makes: {}", element_name(cell.spawn_type)));
+        }
+        if cell.resting {
+            lines.push("Resting".to_string());
+        }
+        lines.push(format!("Charge: {}", self.charge[y as usize][x as usize]));
+        lines.push(format!("Force: {:.1}, {:.1}", force.0, force.1));
+
+        Some(lines)
+    }
+
+    // Makes a grid out of ASCII art with one character for every cell, see element_symbol
+    //
+    // The art can start with a header like the one to_ascii writes, with the size and seed
+    // of the world, or it can just be the rows. Spaces around rows and empty lines are
+    // skipped so scenes can be written indented in code. Sources make water since the
+    // characters only say what element a cell is
+    pub fn from_ascii(text: &str) -> Result<Grid, String> {
+        let mut lines = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .peekable();
+
+        let mut size: Option<(i64, i64)> = None;
+        let mut seed: Option<u64> = None;
+        if lines.peek() == Some(&ASCII_HEADER) {
+            lines.next();
+            for line in lines.by_ref() {
+                let parts: Vec<&str> = line.split_whitespace().collect();
+                let number = |value: &str| {
+                    value
+                        .parse::<i64>()
+                        .map_err(|_| format!("bad value \"{}\"", value))
+                };
+                match parts.as_slice() {
+                    ["size", width, height] => size = Some((number(width)?, number(height)?)),
+                    ["seed", value] => {
+                        seed = Some(
+                            value
+                                .parse()
+                                .map_err(|_| format!("bad seed \"{}\"", value))?,
+                        )
+                    }
+                    ["cells"] => break,
+                    _ => return Err(format!("unknown setting \"{}\"", line)),
+                }
+            }
+        }
+
+        let rows: Vec<Vec<char>> = lines.map(|line| line.chars().collect()).collect();
+        let width = rows.first().map_or(0, |row| row.len()) as i64;
+        let height = rows.len() as i64;
+        if width == 0 {
+            return Err("there are no cells".to_string());
+        }
+        if let Some((size_width, size_height)) = size
+            && (size_width, size_height) != (width, height)
+        {
+            return Err(format!(
+                "size says {}x{} but the cells are {}x{}",
+                size_width, size_height, width, height
+            ));
+        }
+
+        let mut grid = Grid::new(width, height);
+        if let Some(seed) = seed {
+            grid.seed(seed);
+        }
+        for (y, row) in rows.iter().enumerate() {
+            if row.len() as i64 != width {
+                return Err(format!("row {} has the wrong width", y));
+            }
+            for (x, &symbol) in row.iter().enumerate() {
+                let cell_type = element_from_symbol(symbol)
+                    .ok_or_else(|| format!("unknown element {:?} at {}, {}", symbol, x, y))?;
+                grid.grid[y][x] = Cell::from_type(cell_type);
+            }
+        }
+
+        Ok(grid)
+    }
+
+    // Writes the grid as ASCII art that from_ascii reads back, only the element of every
+    // cell is kept. The header has the size and the seed if the grid was seeded
+    pub fn to_ascii(&self) -> String {
+        let mut text = format!("{}\nsize {} {}\n", ASCII_HEADER, self.width, self.height);
+        if let Some(seed) = self.seed {
+            text.push_str(&format!("seed {}\n", seed));
+        }
+        text.push_str("cells\n");
+        for row in &self.grid {
+            text.extend(row.iter().map(|cell| element_symbol(cell.cell_type)));
+            text.push('\n');
+        }
+        text
+    }
+
     // Main update function for cells
     pub fn update(&mut self) {
+        let started = Instant::now();
+
         // Clear processed flags
         for row in &mut self.processed {
             for cell in row {
@@ -92,13 +470,18 @@ impl Grid {
             }
         }
 
-        // Track all cells
-        let mut sand_count: u32 = 0;
-        let mut water_count: u32 = 0;
-        let mut wet_sand_count: u32 = 0;
-        let mut fire_count: u32 = 0;
-        let mut smoke_count: u32 = 0;
-        let mut steam_count: u32 = 0;
+        // What every cell was before the tick, to find the chunks that changed
+        let before: Vec<Vec<u8>> = self
+            .grid
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.cell_type).collect())
+            .collect();
+
+        self.tick += 1;
+        self.update_charge();
+        if self.rigid_bodies {
+            self.update_rigid_bodies();
+        }
 
         for y in 0..self.height {
             for x in 0..self.width {
@@ -106,90 +489,177 @@ impl Grid {
                     continue;
                 }
                 let cell_type = self.grid[y as usize][x as usize].cell_type;
+                // Aging and reactions happen every tick, even for cells that fly or get pushed
+                if !self.react(x, y, cell_type) {
+                    continue;
+                }
+                // Particles thrown by a blast fly until they land
+                if self.update_velocity(x, y) {
+                    continue;
+                }
+                // Being pushed by wind or a force field takes the place of moving
+                if self.apply_force(x, y) {
+                    continue;
+                }
                 match cell_type {
-                    SAND_CELL => {
-                        self.update_sand(x, y);
-                        sand_count += 1;
-                    }
-                    WATER_CELL => {
-                        self.update_water(x, y);
-                        water_count += 1;
-                    }
-                    WET_SAND_CELL => {
-                        self.update_wet_sand(x, y);
-                        wet_sand_count += 1;
+                    SAND_CELL | WET_SAND_CELL | SALT_CELL | GUNPOWDER_CELL => {
+                        self.update_sand(x, y)
                     }
-                    FIRE_CELL => {
-                        self.update_fire(x, y);
-                        fire_count += 1
+                    WATER_CELL | SALT_WATER_CELL | HONEY_CELL | OIL_CELL | LAVA_CELL => {
+                        self.update_water(x, y)
                     }
-                    SMOKE_CELL => {
-                        self.update_smoke(x, y);
-                        smoke_count += 1;
-                    }
-                    STEAM_CELL => {
-                        self.update_steam(x, y);
-                        steam_count += 1;
+                    FIRE_CELL => self.update_fire(x, y),
+                    SMOKE_CELL | STEAM_CELL | HYDROGEN_CELL | OXYGEN_CELL | CO2_CELL => {
+                        self.move_gas(x, y)
                     }
                     _ => {}
                 }
             }
         }
-        print!(
-            "\rSAND: {}, WATER: {}, WET_SAND: {} FIRE: {} SMOKE: {} STEAM: {}",
-            sand_count, water_count, wet_sand_count, fire_count, smoke_count, steam_count
-        );
-        io::stdout().flush().unwrap();
+
+        self.update_pressure();
+
+        for row in &mut self.changed_chunks {
+            row.fill(false);
+        }
+        for (y, row) in before.iter().enumerate() {
+            for (x, &cell_type) in row.iter().enumerate() {
+                if self.grid[y][x].cell_type != cell_type {
+                    self.changed_chunks[y / CHUNK_SIZE as usize][x / CHUNK_SIZE as usize] = true;
+                }
+            }
+        }
+
+        self.update_time = started.elapsed();
+    }
+
+    // How many cells of every element there are, which tick it is and how long the
+    // last update took
+    pub fn stats(&self) -> Stats {
+        let mut counts = [0; ELEMENT_COUNT as usize];
+        for cell in self.grid.iter().flatten() {
+            if let Some(count) = counts.get_mut(cell.cell_type as usize) {
+                *count += 1;
+            }
+        }
+
+        Stats {
+            tick: self.tick,
+            counts,
+            update_time: self.update_time,
+        }
     }
 
-    // Rules of sand
-    // 1) It first tryes to move down
-    // 2) Then diagonally left or right
+    // Rules of sand, every powder uses these rules
+    // 1) It first tryes to move down, or sinks if there is liquid under it
+    // 2) Then diagonally left or right, its friction decides how often it gets to slide
+    // 3) Once it stops sliding it rests until something next to it moves
+    //
+    // Down is whatever way gravity points, without gravity powders stay where they are
     pub fn update_sand(&mut self, x: i64, y: i64) {
-        let targets = [(x, y + 1), (x - 1, y + 1), (x + 1, y + 1)];
-
-        for (tx, ty) in targets {
-            match self
-                .grid
-                .get(ty as usize)
-                .and_then(|row| row.get(tx as usize))
-                .map(|cell| cell.cell_type)
-            {
-                Some(EMPTY_CELL) => {
-                    self.move_particle(x, y, tx, ty);
-                    return; // We dont want it to make more than one move a tick
-                }
-                _ => {}
+        let cell_type = self.grid[y as usize][x as usize].cell_type;
+        let Some(down) = self.pull() else {
+            return;
+        };
+        let (below_x, below_y) = (x + down.0, y + down.1);
+
+        match self.cell_type_at(below_x, below_y) {
+            Some(EMPTY_CELL) => {
+                self.move_particle(x, y, below_x, below_y);
+                return; // We dont want it to make more than one move a tick
             }
+            Some(below) if is_liquid(below) => {
+                self.swap_particle(x, y, below_x, below_y);
+                return;
+            }
+            _ => {}
+        }
+
+        if self.grid[y as usize][x as usize].resting {
+            return;
+        }
+
+        if self.rng.random_range(0.0..1.0) < powder_properties(cell_type).friction {
+            self.grid[y as usize][x as usize].resting = true;
+            return;
         }
+
+        let mut diagonals = [rotate_direction(down, 1), rotate_direction(down, -1)];
+        diagonals.shuffle(&mut self.rng);
+        for (dx, dy) in diagonals {
+            if self.cell_type_at(x + dx, y + dy) == Some(EMPTY_CELL) {
+                self.move_particle(x, y, x + dx, y + dy);
+                return;
+            }
+        }
+
+        self.grid[y as usize][x as usize].resting = true;
     }
 
     // Rules of water
     // 1) Its first goal is to move down, if it can it will if it cant it wont
     // 2) Then it tries to move diagonally to try and move down
     // 3) It will try to move left and right
+    //
+    // Every liquid uses these rules, heavier liquids also sink below lighter ones and
+    // the liquid properties decide how often and how far it spreads sideways.
+    // Down is whatever way gravity points, without gravity liquids stay where they are
     pub fn update_water(&mut self, x: i64, y: i64) {
-        self.water_to_wet_sand(x, y);
+        let cell_type = self.grid[y as usize][x as usize].cell_type;
+        let properties = liquid_properties(cell_type);
+        let Some(down) = self.pull() else {
+            return;
+        };
+        let (below_x, below_y) = (x + down.0, y + down.1);
+
+        // Priority 0: Sink below lighter liquids
+        if let Some(below) = self.cell_type_at(below_x, below_y)
+            && is_liquid(below)
+            && density(below) < density(cell_type)
+        {
+            self.swap_particle(x, y, below_x, below_y);
+            return;
+        }
 
         // Priority 1: Fall straight down
-        if self.try_move_water(x, y, x, y + 1) {
+        if self.try_move_water(x, y, below_x, below_y) {
+            return;
+        }
+
+        // Thick liquids only spread some of the time
+        if self.rng.random_range(0.0..1.0) < properties.viscosity {
             return;
         }
 
         // Priority 2: Fall diagonally (randomize left/right)
-        let mut diagonals = [(x - 1, y + 1), (x + 1, y + 1)];
-        diagonals.shuffle(&mut rand::rng());
-        for (tx, ty) in diagonals {
-            if self.try_move_water(x, y, tx, ty) {
+        let mut diagonals = [rotate_direction(down, 1), rotate_direction(down, -1)];
+        diagonals.shuffle(&mut self.rng);
+        for (dx, dy) in diagonals {
+            if self.try_move_water(x, y, x + dx, y + dy) {
                 return;
             }
         }
 
-        // Priority 3: Spread horizontally
-        let mut horizontals = [(x - 1, y), (x + 1, y)];
-        horizontals.shuffle(&mut rand::rng());
-        for (tx, ty) in horizontals {
-            if self.try_move_water(x, y, tx, ty) {
+        // Priority 3: Spread sideways, up to its flow distance
+        let mut directions = [rotate_direction(down, 2), rotate_direction(down, -2)];
+        directions.shuffle(&mut self.rng);
+        for (dx, dy) in directions {
+            let (mut tx, mut ty) = (x, y);
+            for _ in 0..properties.flow_distance {
+                if self.cell_type_at(tx + dx, ty + dy) != Some(EMPTY_CELL) {
+                    break;
+                }
+                tx += dx;
+                ty += dy;
+
+                // Stop over a drop so it falls next tick
+                if self.cell_type_at(tx + down.0, ty + down.1) == Some(EMPTY_CELL) {
+                    break;
+                }
+            }
+
+            if (tx, ty) != (x, y) {
+                self.move_particle(x, y, tx, ty);
                 return;
             }
         }
@@ -210,6 +680,63 @@ impl Grid {
         cells
     }
 
+    // Element type at the giving cords, None if a wall is there
+    // Cords past a void edge count as empty and cords past a wrapping edge come back in
+    // on the other side
+    fn cell_type_at(&self, x: i64, y: i64) -> Option<u8> {
+        match self.target(x, y) {
+            Target::Inside(x, y) => Some(self.grid[y as usize][x as usize].cell_type),
+            Target::Wall => None,
+            Target::Void => Some(EMPTY_CELL),
+        }
+    }
+
+    // Where a particle ends up when it moves to the giving cords
+    // Walls win over void when a corner is between two different edges
+    fn target(&self, x: i64, y: i64) -> Target {
+        let edge_x = if x < 0 {
+            Some(self.boundaries.left)
+        } else if x >= self.width {
+            Some(self.boundaries.right)
+        } else {
+            None
+        };
+        let edge_y = if y < 0 {
+            Some(self.boundaries.top)
+        } else if y >= self.height {
+            Some(self.boundaries.bottom)
+        } else {
+            None
+        };
+
+        let edges = [edge_x, edge_y];
+        if edges.contains(&Some(Boundary::Wall)) {
+            Target::Wall
+        } else if edges.contains(&Some(Boundary::Void)) {
+            Target::Void
+        } else {
+            Target::Inside(x.rem_euclid(self.width), y.rem_euclid(self.height))
+        }
+    }
+
+    // Direction gravity pulls in, None if there is no gravity
+    fn down(&self) -> Option<(i64, i64)> {
+        if self.gravity == (0, 0) || self.gravity_strength <= 0.0 {
+            None
+        } else {
+            Some(self.gravity)
+        }
+    }
+
+    // Direction gravity pulls in this tick, weak gravity only pulls some of the ticks
+    fn pull(&mut self) -> Option<(i64, i64)> {
+        let down = self.down()?;
+        if self.gravity_strength < 1.0 && !self.rng.random_bool(self.gravity_strength as f64) {
+            return None;
+        }
+        Some(down)
+    }
+
     // Gets the cells of the at the water and then if they are sand it turns it into wet sand
     fn water_to_wet_sand(&mut self, x: i64, y: i64) {
         let cells = self.get_square_area(x, y);
@@ -226,49 +753,28 @@ impl Grid {
     // Tryes to move the water with the rules of moving water, if it cant move it will return false
     // if it can move it will return true
     fn try_move_water(&mut self, x: i64, y: i64, tx: i64, ty: i64) -> bool {
-        if tx < 0 || ty < 0 {
+        if self.cell_type_at(tx, ty) != Some(EMPTY_CELL) {
             return false;
         }
-        if self
-            .grid
-            .get(ty as usize)
-            .and_then(|row| row.get(tx as usize))
-            .map(|cell| cell.cell_type)
-            == Some(0)
-        {
-            self.move_particle(x, y, tx, ty)
-        }
-        false
-    }
-
-    // Rules of sand
-    // 1) It can only move down
-    // 2) If the wet sand is below any water it will swap with its positions with the water
-    // to make a sinking effect
-    fn update_wet_sand(&mut self, x: i64, y: i64) {
-        let tx = x;
-        let ty = y + 1;
-
-        match self
-            .grid
-            .get(ty as usize)
-            .and_then(|row| row.get(tx as usize))
-            .map(|cell| cell.cell_type)
-        {
-            Some(EMPTY_CELL) => self.move_particle(x, y, tx, ty),
-            Some(WATER_CELL) => self.swap_particle(x, y, tx, ty),
-            _ => {}
-        }
+        self.move_particle(x, y, tx, ty);
+        true
     }
 
     // tx: Target X
     // ty: Target Y
     // Swaps a particle from position to taget position
     fn swap_particle(&mut self, x: i64, y: i64, tx: i64, ty: i64) {
+        let (tx, ty) = match self.target(tx, ty) {
+            Target::Inside(tx, ty) => (tx, ty),
+            Target::Wall => return,
+            Target::Void => return self.remove_particle(x, y),
+        };
         let cell_buffer = self.grid[ty as usize][tx as usize];
         self.grid[ty as usize][tx as usize] = self.grid[y as usize][x as usize];
         self.grid[y as usize][x as usize] = cell_buffer;
         self.processed[ty as usize][tx as usize] = true;
+        self.wake_area(x, y);
+        self.wake_area(tx, ty);
     }
 
     // tx: Target X
@@ -276,9 +782,66 @@ impl Grid {
     // Moves a particle to target position
     // Note: Replaces the x and y position with a empty cell
     fn move_particle(&mut self, x: i64, y: i64, tx: i64, ty: i64) {
+        let (tx, ty) = match self.target(tx, ty) {
+            Target::Inside(tx, ty) => (tx, ty),
+            Target::Wall => return,
+            Target::Void => return self.remove_particle(x, y),
+        };
         self.grid[ty as usize][tx as usize] = self.grid[y as usize][x as usize];
         self.grid[y as usize][x as usize] = Cell::new_empty();
         self.processed[ty as usize][tx as usize] = true;
+
+        // Gas drifting past a pile shouldn't make it slide
+        if !is_gas(self.grid[ty as usize][tx as usize].cell_type) {
+            self.wake_area(x, y);
+            self.wake_area(tx, ty);
+        }
+    }
+
+    // Removes a particle that fell out of the world through a void edge
+    fn remove_particle(&mut self, x: i64, y: i64) {
+        self.grid[y as usize][x as usize] = Cell::new_empty();
+        self.wake_area(x, y);
+    }
+
+    // Wakes up any resting powder around the giving cords
+    fn wake_area(&mut self, x: i64, y: i64) {
+        for (cx, cy) in self.get_square_area(x, y) {
+            self.grid[cy as usize][cx as usize].resting = false;
+        }
+    }
+
+    // The rules of a cell that aren't about moving, like burning out or reacting with
+    // what is around it. Returns false if the cell is gone or turned into something else
+    fn react(&mut self, x: i64, y: i64, cell_type: u8) -> bool {
+        match cell_type {
+            WATER_CELL | SALT_WATER_CELL => self.water_to_wet_sand(x, y),
+            FIRE_CELL => {
+                self.update_life_time(x, y);
+                self.fire_to_glass(x, y);
+                self.fire_make_smoke(x, y);
+                self.fire_to_steam(x, y);
+                self.fire_burn_hydrogen(x, y);
+                self.fire_burn_gunpowder(x, y);
+                self.fire_burn_oil(x, y);
+            }
+            SMOKE_CELL | STEAM_CELL => self.update_life_time(x, y),
+            GUNPOWDER_CELL => self.ignite_gunpowder(x, y),
+            SALT_CELL => self.dissolve_salt(x, y),
+            LAVA_CELL => {
+                self.fire_to_glass(x, y);
+                self.fire_to_steam(x, y);
+                self.fire_burn_hydrogen(x, y);
+                self.fire_burn_gunpowder(x, y);
+                self.fire_burn_oil(x, y);
+            }
+            SOURCE_CELL => self.update_source(x, y),
+            VOID_CELL => self.update_void(x, y),
+            CLONE_CELL => self.update_clone(x, y),
+            _ => {}
+        }
+
+        self.grid[y as usize][x as usize].cell_type == cell_type
     }
 
     // Updates life time for a cell
@@ -289,32 +852,31 @@ impl Grid {
             >= self.grid[y as usize][x as usize].max_life_time
         {
             self.grid[y as usize][x as usize] = Cell::new_empty();
-            return;
         }
     }
 
-    // Updates cell based of grass partible rules
+    // Moves fire, it burns things in react
     fn update_fire(&mut self, x: i64, y: i64) {
-        self.update_life_time(x, y);
-
-        self.fire_to_glass(x, y);
-        self.fire_make_smoke(x, y);
-        self.fire_to_steam(x, y);
+        // Fire rises against gravity, without gravity it stays where it is
+        let Some(down) = self.pull() else {
+            return;
+        };
+        let up = (-down.0, -down.1);
 
-        let mut targets = [(x, y - 1), (x - 1, y - 1), (x + 1, y - 1)];
-        targets.shuffle(&mut rand::rng());
+        let mut targets = [up, rotate_direction(up, 1), rotate_direction(up, -1)];
+        targets.shuffle(&mut self.rng);
 
-        for (tx, ty) in targets {
-            if !self.try_move_gass(x, y, tx, ty) {
+        for (dx, dy) in targets {
+            if !self.try_move_gass(x, y, x + dx, y + dy) {
                 continue;
             }
         }
 
-        let mut targets = [(x - 1, y), (x + 1, y)];
-        targets.shuffle(&mut rand::rng());
+        let mut targets = [rotate_direction(up, 2), rotate_direction(up, -2)];
+        targets.shuffle(&mut self.rng);
 
-        for (tx, ty) in targets {
-            if !self.try_move_gass(x, y, tx, ty) {
+        for (dx, dy) in targets {
+            if !self.try_move_gass(x, y, x + dx, y + dy) {
                 continue;
             }
         }
@@ -332,46 +894,62 @@ impl Grid {
     }
 
     // Has a random chance to make smoke at a giving point
+    // The smoke only goes in a empty cell so it never replaces something that is there
     fn fire_make_smoke(&mut self, x: i64, y: i64) {
-        let random_number = rand::random_range(0..100);
+        let random_number = self.rng.random_range(0..100);
         if random_number > 98 {
             let cells: Vec<(i64, i64)> = self.get_square_area(x, y);
 
             if !cells.is_empty() {
-                let random_cell = cells[rand::random_range(0..cells.len())];
+                let random_cell = cells[self.rng.random_range(0..cells.len())];
                 let (sx, sy) = random_cell;
 
-                self.grid[sy as usize][sx as usize] = Cell::new_smoke();
+                if self.grid[sy as usize][sx as usize].cell_type == EMPTY_CELL {
+                    self.grid[sy as usize][sx as usize] = Cell::new_smoke();
+                }
             }
         }
     }
 
-    // Moves smoke based of the gass particle rules
+    // Moves a cell based of the gass particle rules
     //
-    // First
-    // 1) First it tries to move up or diagonally
-    // 2) And then tries to move left or right
-    fn update_smoke(&mut self, x: i64, y: i64) {
-        self.update_life_time(x, y);
-
-        // Priority 1: fall diagonally or upwards
-        let mut targets = [(x, y - 1), (x - 1, y - 1), (x + 1, y - 1)];
-        targets.shuffle(&mut rand::rng());
-
-        for (tx, ty) in targets {
-            if !self.try_move_gass(x, y, tx, ty) {
-                continue;
-            }
+    // 1) Gases lighter than air try to rise and gases heavier than air try to sink,
+    //    the bigger the difference the more often they do it
+    // 2) The rest of the time they wander in a random direction so they spread out and mix
+    fn move_gas(&mut self, x: i64, y: i64) {
+        let cell_type = self.grid[y as usize][x as usize].cell_type;
+        if cell_type == EMPTY_CELL {
+            return;
         }
 
-        // Priority 2: move left/right
+        // Without gravity gases only wander
+        let buoyancy = density(cell_type) - AIR_DENSITY;
+        let buoyancy_chance =
+            ((0.3 + buoyancy.abs() as f64) * self.gravity_strength as f64).clamp(0.0, 0.9);
+        let rising = self
+            .down()
+            .filter(|_| self.rng.random_bool(buoyancy_chance));
 
-        let mut targets = [(x - 1, y), (x + 1, y)];
-        targets.shuffle(&mut rand::rng());
+        let mut targets: Vec<(i64, i64)> = match rising {
+            Some(down) => {
+                let direction = if buoyancy < 0.0 {
+                    (-down.0, -down.1)
+                } else {
+                    down
+                };
+                vec![
+                    direction,
+                    rotate_direction(direction, 1),
+                    rotate_direction(direction, -1),
+                ]
+            }
+            None => DIRECTIONS.to_vec(),
+        };
+        targets.shuffle(&mut self.rng);
 
-        for (tx, ty) in targets {
-            if !self.try_move_gass(x, y, tx, ty) {
-                continue;
+        for (dx, dy) in targets {
+            if self.try_move_gass(x, y, x + dx, y + dy) {
+                return;
             }
         }
     }
@@ -379,78 +957,722 @@ impl Grid {
     // tx: Target X
     // ty: Target Y
     // Trys to move a gass particle, if it cant it returns false, if it can it returns true
+    //
+    // Gases can also swap with other gases, a lighter gas swaps with a heavier gas above it,
+    // a heavier gas swaps with a lighter one below it and gases next to each other
+    // sometimes swap so they mix, above and below follow gravity
     fn try_move_gass(&mut self, x: i64, y: i64, tx: i64, ty: i64) -> bool {
-        if tx < 0 || ty < 0 || tx >= self.width || ty >= self.height {
+        let Some(target_type) = self.cell_type_at(tx, ty) else {
+            return false;
+        };
+        let cell_type = self.grid[y as usize][x as usize].cell_type;
+        if cell_type == EMPTY_CELL {
             return false;
         }
-        if self
-            .grid
-            .get(ty as usize)
-            .and_then(|row| row.get(tx as usize))
-            .map(|cell| cell.cell_type)
-            == Some(0)
-        {
+
+        if target_type == EMPTY_CELL {
             self.move_particle(x, y, tx, ty);
             return true;
         }
+
+        if is_gas(target_type) && target_type != cell_type {
+            // How far the move goes with gravity, below 0 means it moves up
+            let fall = (tx - x) * self.gravity.0 + (ty - y) * self.gravity.1;
+            let swap = match fall.cmp(&0) {
+                Ordering::Less => density(cell_type) < density(target_type),
+                Ordering::Greater => density(cell_type) > density(target_type),
+                Ordering::Equal => self.rng.random_bool(GAS_MIX_CHANCE),
+            };
+            if swap {
+                self.swap_particle(x, y, tx, ty);
+                return true;
+            }
+        }
+
         false
     }
 
-    // Moves the steam based of the gass particle rules
-    fn update_steam(&mut self, x: i64, y: i64) {
-        self.update_life_time(x, y);
+    // If theres any water around the giving cords it will turn into steam
+    fn fire_to_steam(&mut self, x: i64, y: i64) {
+        let cells: Vec<(i64, i64)> = self.get_square_area(x, y);
+
+        for (x, y) in cells {
+            match self.grid[y as usize][x as usize].cell_type {
+                WATER_CELL => self.grid[y as usize][x as usize] = Cell::new_steam(),
+                SALT_WATER_CELL => self.boil_salt_water(x, y),
+                _ => {}
+            }
+        }
+    }
 
-        let mut targets = [(x, y - 1), (x - 1, y - 1), (x + 1, y - 1)];
-        targets.shuffle(&mut rand::rng());
+    // Boiling salt water leaves its salt behind and lets off steam next to it
+    fn boil_salt_water(&mut self, x: i64, y: i64) {
+        self.grid[y as usize][x as usize] = Cell::new_salt();
 
-        for (tx, ty) in targets {
-            if tx < 0 || ty < 0 || tx >= self.width || ty >= self.height {
-                continue;
+        let mut cells: Vec<(i64, i64)> = self.get_square_area(x, y);
+        cells.shuffle(&mut self.rng);
+
+        for (cx, cy) in cells {
+            if self.grid[cy as usize][cx as usize].cell_type == EMPTY_CELL {
+                self.grid[cy as usize][cx as usize] = Cell::new_steam();
+                return;
             }
-            match self
-                .grid
-                .get(ty as usize)
-                .and_then(|row| row.get(tx as usize))
-                .map(|cell| cell.cell_type)
-            {
-                Some(EMPTY_CELL) => {
-                    self.move_particle(x, y, tx, ty);
-                    return;
+        }
+    }
+
+    // If salt touches water it dissolves and turns the water into salt water,
+    // otherwise it falls like sand
+    fn dissolve_salt(&mut self, x: i64, y: i64) {
+        let cells = self.get_square_area(x, y);
+
+        for (cx, cy) in cells {
+            if self.grid[cy as usize][cx as usize].cell_type == WATER_CELL {
+                self.grid[cy as usize][cx as usize] = Cell::new_salt_water();
+                self.grid[y as usize][x as usize] = Cell::new_empty();
+                return;
+            }
+        }
+    }
+
+    // If gunpowder touches fire or lava it blows up, otherwise it falls like sand
+    fn ignite_gunpowder(&mut self, x: i64, y: i64) {
+        let cells = self.get_square_area(x, y);
+
+        for (cx, cy) in cells {
+            let cell_type = self.grid[cy as usize][cx as usize].cell_type;
+            if cell_type == FIRE_CELL || cell_type == LAVA_CELL {
+                self.explode(x, y, GUNPOWDER_BLAST_POWER);
+                return;
+            }
+        }
+    }
+
+    // Blows up everything around the giving cords
+    //
+    // The blast gets weaker the further it gets from the center, any cell where the blast is
+    // stronger than its blast resistance gets destroyed and leaves fire or smoke behind.
+    // Loose cells just outside of that get thrown away from the center.
+    // Anything the blast can't break holds it back, so steel walls shield what is behind them
+    pub fn explode(&mut self, x: i64, y: i64, power: f32) {
+        let throw_radius = (power * 2.0).ceil() as i32;
+        // Worked out before anything breaks so the order the cells go in doesn't matter
+        let positions: Vec<(i32, i32)> = self
+            .get_circle_positions(x as i32, y as i32, throw_radius)
+            .into_iter()
+            .filter(|&(px, py)| self.blast_reaches(x, y, px as i64, py as i64, power))
+            .collect();
+
+        for (px, py) in positions {
+            let dx = (px as i64 - x) as f32;
+            let dy = (py as i64 - y) as f32;
+            let distance = (dx * dx + dy * dy).sqrt();
+            let cell = &mut self.grid[py as usize][px as usize];
+
+            if power - distance > blast_resistance(cell.cell_type) {
+                *cell = match self.rng.random_range(0..10) {
+                    0..5 => Cell::new_fire(),
+                    5..7 => Cell::new_smoke(),
+                    _ => Cell::new_empty(),
+                };
+            } else if is_debris(cell.cell_type) && distance > 0.0 {
+                // The throw radius is rounded up, so the edge of it can be past where the
+                // blast still has force left
+                let force = (power * 2.0 - distance) / 2.0;
+                if force > 0.0 {
+                    cell.velocity_x = dx / distance * force;
+                    cell.velocity_y = dy / distance * force;
                 }
-                _ => {}
             }
         }
+    }
+
+    // If a blast at x, y gets to the cell at px, py
+    // Every cell on the way that is too strong for the blast and doesn't get thrown by it
+    // stops it, loose cells pass the push on and gases and fire let it through
+    fn blast_reaches(&self, x: i64, y: i64, px: i64, py: i64, power: f32) -> bool {
+        let line = line_positions((x as i32, y as i32), (px as i32, py as i32));
+        let between = &line[1..line.len().saturating_sub(1).max(1)];
+
+        between.iter().all(|&(lx, ly)| {
+            let cell_type = self.grid[ly as usize][lx as usize].cell_type;
+            let dx = (lx as i64 - x) as f32;
+            let dy = (ly as i64 - y) as f32;
+            let distance = (dx * dx + dy * dy).sqrt();
+
+            cell_type == EMPTY_CELL
+                || cell_type == FIRE_CELL
+                || is_gas(cell_type)
+                || is_debris(cell_type)
+                || power - distance > blast_resistance(cell_type)
+        })
+    }
+
+    // Moves a cell that is flying through the air along its velocity
+    // Returns true if the cell moved this way, so it doesn't also move by its own rules
+    fn update_velocity(&mut self, x: i64, y: i64) -> bool {
+        let cell = self.grid[y as usize][x as usize];
+        if cell.velocity_x == 0.0 && cell.velocity_y == 0.0 {
+            return false;
+        }
 
-        let mut targets = [(x - 1, y), (x + 1, y)];
-        targets.shuffle(&mut rand::rng());
+        let velocity_x = cell.velocity_x.clamp(-MAX_VELOCITY, MAX_VELOCITY);
+        let velocity_y = cell.velocity_y.clamp(-MAX_VELOCITY, MAX_VELOCITY);
+        let steps = velocity_x.abs().max(velocity_y.abs()).ceil() as i64;
 
-        for (tx, ty) in targets {
-            if tx < 0 || ty < 0 || tx >= self.width || ty >= self.height {
+        // Walk the path one step at a time so particles can't skip through walls
+        // Path is the cords along the path, current is where the cell is now in the grid
+        let (mut path_x, mut path_y) = (x, y);
+        let (mut cx, mut cy) = (x, y);
+        let mut landed = false;
+        for step in 1..=steps {
+            let tx = x + (velocity_x * step as f32 / steps as f32).round() as i64;
+            let ty = y + (velocity_y * step as f32 / steps as f32).round() as i64;
+            if tx == path_x && ty == path_y {
                 continue;
             }
-            match self
-                .grid
-                .get(ty as usize)
-                .and_then(|row| row.get(tx as usize))
-                .map(|cell| cell.cell_type)
-            {
-                Some(EMPTY_CELL) => {
-                    self.move_particle(x, y, tx, ty);
-                    return;
+            (path_x, path_y) = (tx, ty);
+
+            match self.target(tx, ty) {
+                Target::Inside(tx, ty)
+                    if self.grid[ty as usize][tx as usize].cell_type == EMPTY_CELL =>
+                {
+                    self.move_particle(cx, cy, tx, ty);
+                    cx = tx;
+                    cy = ty;
+                }
+                Target::Void => {
+                    self.remove_particle(cx, cy);
+                    return true;
+                }
+                _ => {
+                    landed = true;
+                    break;
                 }
-                _ => {}
             }
         }
+
+        // Air slows it down and gravity pulls on it
+        let (gravity_x, gravity_y) = (self.gravity.0 as f32, self.gravity.1 as f32);
+        let gravity_length = (gravity_x * gravity_x + gravity_y * gravity_y)
+            .sqrt()
+            .max(1.0);
+        let pull = FLYING_GRAVITY * self.gravity_strength;
+        let cell = &mut self.grid[cy as usize][cx as usize];
+        cell.velocity_x = velocity_x * 0.95 + gravity_x / gravity_length * pull;
+        cell.velocity_y = velocity_y * 0.95 + gravity_y / gravity_length * pull;
+        if landed || cell.velocity_x.abs().max(cell.velocity_y.abs()) < 0.1 {
+            cell.velocity_x = 0.0;
+            cell.velocity_y = 0.0;
+        }
+        self.processed[cy as usize][cx as usize] = true;
+
+        true
     }
 
-    // If theres any water around the giving cords it will turn into steam
-    fn fire_to_steam(&mut self, x: i64, y: i64) {
+    // Rules of sources
+    // 1) Fills every empty cell around it with its element
+    fn update_source(&mut self, x: i64, y: i64) {
+        let spawn_type = self.grid[y as usize][x as usize].spawn_type;
+        if spawn_type == EMPTY_CELL {
+            return;
+        }
+
+        let cells = self.get_square_area(x, y);
+
+        for (cx, cy) in cells {
+            if self.grid[cy as usize][cx as usize].cell_type == EMPTY_CELL {
+                self.grid[cy as usize][cx as usize] = Cell::from_type(spawn_type);
+                self.processed[cy as usize][cx as usize] = true;
+            }
+        }
+    }
+
+    // Rules of voids
+    // 1) Deletes any particle touching it
+    fn update_void(&mut self, x: i64, y: i64) {
+        let cells = self.get_square_area(x, y);
+
+        for (cx, cy) in cells {
+            if !is_solid(self.grid[cy as usize][cx as usize].cell_type) {
+                self.grid[cy as usize][cx as usize] = Cell::new_empty();
+            }
+        }
+    }
+
+    // Rules of clones
+    // 1) Until it has a element it copies the first particle that touches it
+    // 2) After that it acts like a source of that element
+    fn update_clone(&mut self, x: i64, y: i64) {
+        if self.grid[y as usize][x as usize].spawn_type == EMPTY_CELL {
+            let cells = self.get_square_area(x, y);
+
+            for (cx, cy) in cells {
+                let cell_type = self.grid[cy as usize][cx as usize].cell_type;
+                if cell_type != EMPTY_CELL && !is_solid(cell_type) {
+                    self.grid[y as usize][x as usize].spawn_type = cell_type;
+                    break;
+                }
+            }
+        }
+
+        self.update_source(x, y);
+    }
+
+    // Blows up any hydrogen around the giving cords
+    fn fire_burn_hydrogen(&mut self, x: i64, y: i64) {
         let cells: Vec<(i64, i64)> = self.get_square_area(x, y);
 
-        for (x, y) in cells {
-            if self.grid[y as usize][x as usize].cell_type == WATER_CELL {
-                self.grid[y as usize][x as usize] = Cell::new_steam();
+        for (cx, cy) in cells {
+            if self.grid[cy as usize][cx as usize].cell_type == HYDROGEN_CELL {
+                self.explode(cx, cy, HYDROGEN_BLAST_POWER);
+            }
+        }
+    }
+
+    // Blows up any gunpowder around the giving cords
+    fn fire_burn_gunpowder(&mut self, x: i64, y: i64) {
+        let cells: Vec<(i64, i64)> = self.get_square_area(x, y);
+
+        for (cx, cy) in cells {
+            if self.grid[cy as usize][cx as usize].cell_type == GUNPOWDER_CELL {
+                self.explode(cx, cy, GUNPOWDER_BLAST_POWER);
+            }
+        }
+    }
+
+    // Sets any oil around the giving cords on fire
+    fn fire_burn_oil(&mut self, x: i64, y: i64) {
+        let cells: Vec<(i64, i64)> = self.get_square_area(x, y);
+
+        for (cx, cy) in cells {
+            if self.grid[cy as usize][cx as usize].cell_type == OIL_CELL {
+                self.grid[cy as usize][cx as usize] = Cell::new_fire();
             }
         }
     }
+
+    // Moves electricity through conductors
+    //
+    // Every idle conductor next to a charged cell or a pulsing battery gets charged, then it
+    // counts down through its refractory period so a pulse travels as a wave and can't flow
+    // back the way it came
+    fn update_charge(&mut self) {
+        let battery_pulse = self.tick.is_multiple_of(BATTERY_PULSE_TICKS);
+        let mut next_charge = vec![vec![0; self.width as usize]; self.height as usize];
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !is_conductor(self.grid[y as usize][x as usize].cell_type) {
+                    continue;
+                }
+
+                let charge = self.charge[y as usize][x as usize];
+                if charge > 0 {
+                    next_charge[y as usize][x as usize] = charge - 1;
+                    continue;
+                }
+
+                for (cx, cy) in self.get_square_area(x, y) {
+                    let neighbor_type = self.grid[cy as usize][cx as usize].cell_type;
+                    if self.charge[cy as usize][cx as usize] == CHARGE_HEAD
+                        || (battery_pulse && neighbor_type == BATTERY_CELL)
+                    {
+                        next_charge[y as usize][x as usize] = CHARGE_HEAD;
+                        break;
+                    }
+                }
+            }
+        }
+
+        self.charge = next_charge;
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.charge[y as usize][x as usize] == CHARGE_HEAD {
+                    self.spark(x, y);
+                }
+            }
+        }
+    }
+
+    // Charged cells set fire to anything flammable next to them and split water into gas
+    fn spark(&mut self, x: i64, y: i64) {
+        let cells: Vec<(i64, i64)> = self.get_square_area(x, y);
+
+        for (cx, cy) in cells {
+            let cell_type = self.grid[cy as usize][cx as usize].cell_type;
+            if is_flammable(cell_type) {
+                self.grid[cy as usize][cx as usize] = Cell::new_fire();
+            } else if cell_type == WATER_CELL {
+                // Water splits into two parts hydrogen and one part oxygen
+                self.grid[cy as usize][cx as usize] = if self.rng.random_range(0..3) < 2 {
+                    Cell::new_hydrogen()
+                } else {
+                    Cell::new_oxygen()
+                };
+            }
+        }
+    }
+
+    // Pushes liquid up into connected chambers until the levels match
+    //
+    // Every connected body of liquid moves cells from its highest surface into its lowest
+    // opening, as long as that opening is below the surface. An opening is a empty cell
+    // next to the body that has something under it, so liquid put there won't just fall
+    fn update_pressure(&mut self) {
+        let Some(down) = self.down() else {
+            return;
+        };
+        // How far down a cell is, measured along gravity
+        let depth = |(x, y): (i64, i64)| x * down.0 + y * down.1;
+        let bodies = self.label_components(is_liquid).groups;
+
+        for body in bodies {
+            let mut surfaces: Vec<(i64, i64)> = Vec::new();
+            let mut openings: Vec<(i64, i64)> = Vec::new();
+            for &(bx, by) in &body {
+                if self.cell_type_at(bx - down.0, by - down.1) == Some(EMPTY_CELL) {
+                    surfaces.push((bx, by));
+                }
+
+                for (ox, oy) in [(bx - 1, by), (bx + 1, by), (bx, by - 1), (bx, by + 1)] {
+                    let Target::Inside(ox, oy) = self.target(ox, oy) else {
+                        continue;
+                    };
+                    let supported = self
+                        .cell_type_at(ox + down.0, oy + down.1)
+                        .is_none_or(|below| below != EMPTY_CELL);
+                    if self.cell_type_at(ox, oy) == Some(EMPTY_CELL) && supported {
+                        openings.push((ox, oy));
+                    }
+                }
+            }
+
+            surfaces.shuffle(&mut self.rng);
+            surfaces.sort_by_key(|&surface| depth(surface));
+            openings.sort();
+            openings.dedup();
+            openings.shuffle(&mut self.rng);
+            openings.sort_by_key(|&opening| std::cmp::Reverse(depth(opening)));
+
+            for (surface, opening) in surfaces
+                .into_iter()
+                .zip(openings)
+                .take(PRESSURE_MOVES_PER_TICK)
+            {
+                if depth(opening) <= depth(surface) {
+                    break;
+                }
+                // Thick liquids get pushed around by pressure as slowly as they flow
+                let surface_type = self.grid[surface.1 as usize][surface.0 as usize].cell_type;
+                if self.rng.random_range(0.0..1.0) < liquid_properties(surface_type).viscosity {
+                    continue;
+                }
+                self.move_particle(surface.0, surface.1, opening.0, opening.1);
+            }
+        }
+    }
+
+    // Labels every group of connected cells where is_member returns true
+    // Cells only count as connected if they touch on a side, not just on a corner.
+    // Cells on wrapping edges are connected to the cells on the other side
+    pub fn label_components(&self, is_member: impl Fn(u8) -> bool) -> Components {
+        let mut labels = vec![vec![0; self.width as usize]; self.height as usize];
+        let mut groups: Vec<Vec<(i64, i64)>> = Vec::new();
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if labels[y as usize][x as usize] != 0
+                    || !is_member(self.grid[y as usize][x as usize].cell_type)
+                {
+                    continue;
+                }
+
+                let label = groups.len() as u32 + 1;
+                let mut group: Vec<(i64, i64)> = Vec::new();
+                let mut stack: Vec<(i64, i64)> = vec![(x, y)];
+                labels[y as usize][x as usize] = label;
+
+                while let Some((cx, cy)) = stack.pop() {
+                    group.push((cx, cy));
+
+                    for (nx, ny) in [(cx - 1, cy), (cx + 1, cy), (cx, cy - 1), (cx, cy + 1)] {
+                        let Target::Inside(nx, ny) = self.target(nx, ny) else {
+                            continue;
+                        };
+                        if labels[ny as usize][nx as usize] == 0
+                            && is_member(self.grid[ny as usize][nx as usize].cell_type)
+                        {
+                            labels[ny as usize][nx as usize] = label;
+                            stack.push((nx, ny));
+                        }
+                    }
+                }
+
+                groups.push(group);
+            }
+        }
+
+        Components { labels, groups }
+    }
+
+    // Drops every rigid body that has nothing holding it up by one cell
+    //
+    // A body is held up by the floor, by another solid, or when enough of what is under it
+    // is powder. Liquid, gas and loose powder under a falling body get pushed up to the
+    // top of the body so it sinks through them
+    //
+    // Columns of the body run along gravity, so bodies fall whichever way gravity points
+    fn update_rigid_bodies(&mut self) {
+        let Some((down_x, down_y)) = self.down() else {
+            return;
+        };
+        let components = self.label_components(is_rigid);
+        let label_at = |grid: &Grid, x: i64, y: i64| match grid.target(x, y) {
+            Target::Inside(x, y) => components.labels[y as usize][x as usize],
+            _ => 0,
+        };
+
+        for (index, body) in components.groups.iter().enumerate() {
+            let label = index as u32 + 1;
+
+            // The bottom cell of every column of the body
+            let bottoms: Vec<(i64, i64)> = body
+                .iter()
+                .copied()
+                .filter(|&(x, y)| label_at(self, x + down_x, y + down_y) != label)
+                .collect();
+
+            let mut powder_contacts = 0;
+            let mut supported = false;
+            for &(x, y) in &bottoms {
+                let below = match self.target(x + down_x, y + down_y) {
+                    Target::Inside(bx, by) => self.grid[by as usize][bx as usize].cell_type,
+                    Target::Wall => {
+                        supported = true;
+                        break;
+                    }
+                    Target::Void => continue,
+                };
+                if is_solid(below) {
+                    supported = true;
+                    break;
+                }
+                if !is_displaceable(below) {
+                    powder_contacts += 1;
+                }
+            }
+            if supported || powder_contacts * 3 > bottoms.len() {
+                continue;
+            }
+
+            // Shift every column down by one and put what was under it on top
+            // Cells shifted into a void edge are gone
+            for &(bottom_x, bottom_y) in &bottoms {
+                let below = self.target(bottom_x + down_x, bottom_y + down_y);
+                let displaced = match below {
+                    Target::Inside(bx, by) => self.grid[by as usize][bx as usize],
+                    _ => Cell::new_empty(),
+                };
+
+                let mut to = below;
+                let (mut x, mut y) = (bottom_x, bottom_y);
+                loop {
+                    if let Target::Inside(tx, ty) = to {
+                        self.grid[ty as usize][tx as usize] = self.grid[y as usize][x as usize];
+                        self.processed[ty as usize][tx as usize] = true;
+                    }
+                    if label_at(self, x - down_x, y - down_y) != label {
+                        break;
+                    }
+                    to = Target::Inside(x, y);
+                    (x, y) = (
+                        (x - down_x).rem_euclid(self.width),
+                        (y - down_y).rem_euclid(self.height),
+                    );
+                }
+                self.grid[y as usize][x as usize] = displaced;
+                self.processed[y as usize][x as usize] = true;
+            }
+        }
+    }
+
+    // Pushes a cell one step with the wind and the force field under it
+    // The stronger the force and the lighter the cell the more often it gets pushed
+    // Returns true if the cell got pushed, so its normal rules get skipped for this tick
+    fn apply_force(&mut self, x: i64, y: i64) -> bool {
+        let (field_x, field_y) = self.force_field[y as usize][x as usize];
+        let force_x = self.wind.0 + field_x;
+        let force_y = self.wind.1 + field_y;
+        let strength = (force_x * force_x + force_y * force_y).sqrt();
+        if strength == 0.0 {
+            return false;
+        }
+
+        let cell_type = self.grid[y as usize][x as usize].cell_type;
+        let chance = (strength * wind_susceptibility(cell_type)).min(1.0);
+        if chance <= 0.0 || self.rng.random_range(0.0..1.0) >= chance {
+            return false;
+        }
+
+        let tx = x + (force_x / strength).round() as i64;
+        let ty = y + (force_y / strength).round() as i64;
+        self.try_move_gass(x, y, tx, ty)
+    }
+}
+
+// What happens at an edge of the grid
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Boundary {
+    // Particles stop at the edge
+    #[default]
+    Wall,
+    // Particles come back in on the opposite edge
+    Wrap,
+    // Particles that go past the edge are deleted
+    Void,
+}
+
+impl Boundary {
+    // The next mode, used to cycle through them
+    pub fn next(self) -> Self {
+        match self {
+            Boundary::Wall => Boundary::Wrap,
+            Boundary::Wrap => Boundary::Void,
+            Boundary::Void => Boundary::Wall,
+        }
+    }
+}
+
+impl fmt::Display for Boundary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Boundary::Wall => "wall",
+            Boundary::Wrap => "wrap",
+            Boundary::Void => "void",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Boundary {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "wall" => Ok(Boundary::Wall),
+            "wrap" => Ok(Boundary::Wrap),
+            "void" => Ok(Boundary::Void),
+            _ => Err(format!("unknown boundary \"{}\"", value)),
+        }
+    }
+}
+
+// Boundary mode of every edge of the grid
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Boundaries {
+    pub left: Boundary,
+    pub right: Boundary,
+    pub top: Boundary,
+    pub bottom: Boundary,
+}
+
+// What the world looked like at a tick, see Grid::stats
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stats {
+    pub tick: u64,
+    // Number of cells of every element, the cell type is the index
+    pub counts: [u32; ELEMENT_COUNT as usize],
+    pub update_time: Duration,
+}
+
+impl Stats {
+    pub fn count(&self, cell_type: u8) -> u32 {
+        self.counts.get(cell_type as usize).copied().unwrap_or(0)
+    }
+
+    // Every cell that isn't empty
+    pub fn particles(&self) -> u32 {
+        self.counts.iter().sum::<u32>() - self.count(EMPTY_CELL)
+    }
+}
+
+// How elements get put down by place_positions
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Placement {
+    // Chance every cell gets placed, below 1 the brush sprays
+    pub density: f64,
+    // Draws over cells that aren't empty
+    pub replace: bool,
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Placement {
+            density: 1.0,
+            replace: false,
+        }
+    }
+}
+
+// Part of the world that stays in place when the grid is resized
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    // How far the old cells move when the grid goes from the old size to the new size
+    fn offset(
+        self,
+        (old_width, old_height): (i64, i64),
+        (new_width, new_height): (i64, i64),
+    ) -> (i64, i64) {
+        let (horizontal, vertical) = match self {
+            Anchor::TopLeft => (0, 0),
+            Anchor::Top => (1, 0),
+            Anchor::TopRight => (2, 0),
+            Anchor::Left => (0, 1),
+            Anchor::Center => (1, 1),
+            Anchor::Right => (2, 1),
+            Anchor::BottomLeft => (0, 2),
+            Anchor::Bottom => (1, 2),
+            Anchor::BottomRight => (2, 2),
+        };
+        (
+            (new_width - old_width) * horizontal / 2,
+            (new_height - old_height) * vertical / 2,
+        )
+    }
+}
+
+// Where a move to some cords ends up, see Grid::target
+enum Target {
+    Inside(i64, i64),
+    Wall,
+    Void,
+}
+
+// Turns a direction by steps of 45 degrees, positive steps turn clockwise on the screen
+pub fn rotate_direction(direction: (i64, i64), steps: i64) -> (i64, i64) {
+    let Some(index) = DIRECTIONS.iter().position(|&d| d == direction) else {
+        return direction;
+    };
+    DIRECTIONS[(index as i64 + steps).rem_euclid(8) as usize]
+}
+
+// Connected groups of cells, see Grid::label_components
+pub struct Components {
+    // Label of the group every cell is in, 0 if its not in any group
+    pub labels: Vec<Vec<u32>>,
+    // Positions of the cells in every group, the group with label n is at index n - 1
+    pub groups: Vec<Vec<(i64, i64)>>,
 }
@@ -0,0 +1,6 @@
+pub mod cell;
+pub mod color;
+pub mod grid;
+pub mod overlay;
+pub mod tools;
+pub mod world;
@@ -1,56 +1,207 @@
-mod cell;
-mod color;
-mod grid;
+mod config;
 mod ui;
 
-use std::time::{Duration, Instant};
-
-use color::*;
-use grid::Grid;
+use std::collections::VecDeque;
+use std::path::Path;
+use std::time::Instant;
+
+use particle_sim::color::*;
+use particle_sim::grid::{self, Anchor, CHUNK_SIZE, Grid, MAX_WORLD_SIZE, Placement, Stats};
+use particle_sim::overlay::ScalarField;
+use particle_sim::tools::{self, Brush, BrushShape, Tool};
+use particle_sim::world::{load_world, save_world};
+use ui::button::UIButton;
+use ui::camera::Camera;
+use ui::graph::Graph;
+use ui::input::Action;
 use ui::text::Label;
+use ui::tooltip::Tooltip;
 
 use piston_window::{
     PistonWindow, WindowSettings,
-    graphics::{Context, Graphics, clear, rectangle},
+    graphics::{Context, Graphics, character, clear, rectangle},
 };
 
 use piston_window::*;
 use wgpu_graphics::TextureSettings;
 
-use crate::cell::*;
+use particle_sim::cell::*;
+
+// Elements that can be picked from the side panel
+const PALETTE: [u8; 16] = [
+    SAND_CELL,
+    STEEL_CELL,
+    WATER_CELL,
+    HONEY_CELL,
+    OIL_CELL,
+    LAVA_CELL,
+    FIRE_CELL,
+    GUNPOWDER_CELL,
+    SALT_CELL,
+    CO2_CELL,
+    SOURCE_CELL,
+    VOID_CELL,
+    CLONE_CELL,
+    WIRE_CELL,
+    BATTERY_CELL,
+    EMPTY_CELL,
+];
+
+// Where the world gets saved to and loaded from
+const WORLD_FILE: &str = "world.txt";
+// How much the wind changes with every click of a wind button
+const WIND_STEP: f32 = 0.1;
+// How hard painted force fields push
+const FORCE_FIELD_STRENGTH: f32 = 1.0;
+
+// How fast WASD moves the camera, in pixels per second
+const PAN_SPEED: f64 = 600.0;
+// How much one step of Ctrl+scroll zooms
+const ZOOM_STEP: f64 = 1.25;
+
+// Biggest the brush can get
+const MAX_BRUSH_SIZE: i32 = 10;
+// Chance a cell gets placed when spraying, and how much [ and ] change it
+const SPRAY_DENSITY: f64 = 0.2;
+const SPRAY_DENSITY_STEP: f64 = 0.1;
+// How many ticks of stats the graphs show
+const STATS_HISTORY: usize = 180;
+// How many changes can be undone
+const UNDO_LIMIT: usize = 20;
+
+// Width of the side panel, the rest of the window shows the world
+pub const PANEL_WIDTH: f64 = 200.0;
+// How many cells the world size buttons add or remove on each side
+const WORLD_SIZE_STEP: i64 = 20;
+// Smallest world the size buttons can make
+const MIN_WORLD_SIZE: i64 = 20;
+
+// How a wind button changes the wind, None turns the wind off
+type WindChange = Option<(f32, f32)>;
 
 fn main() {
-    const CELL_SIZE: f64 = 7.0;
-    const GRID_WIDTH: i64 = 170;
-    const GRID_HEIGHT: i64 = 130;
-    const _FPS: u16 = 60;
-
-    let sand_box_height = (GRID_HEIGHT as f64 * CELL_SIZE) as u32;
-    let sand_box_width = (GRID_WIDTH as f64 * CELL_SIZE) as u32;
-    let window_width: u32 = sand_box_width + 200;
-    let window_height: u32 = sand_box_height;
+    // Settings come from the config file and the command line
+    let settings = match config::load_settings() {
+        Ok(Some(settings)) => settings,
+        Ok(None) => return,
+        Err(error) => {
+            eprintln!("particle-sim: {}", error);
+            std::process::exit(2);
+        }
+    };
+    let (window_width, window_height) = settings.window_size;
+
+    // Size of the part of the window that shows the world, changes when the window does
+    let mut sand_box_width = window_width as f64 - PANEL_WIDTH;
+    let mut sand_box_height = window_height as f64;
+
+    // Start with the world file if one was giving, before the window opens so a bad file
+    // doesn't flash a window
+    let mut grid = match &settings.world {
+        Some(path) => match load_world(path) {
+            Ok(world) => world,
+            Err(error) => {
+                eprintln!("particle-sim: could not load {}: {}", path.display(), error);
+                std::process::exit(2);
+            }
+        },
+        None => Grid::new(settings.grid_size.0, settings.grid_size.1),
+    };
+    if let Some(seed) = settings.seed {
+        grid.seed(seed);
+    }
+    if let Some(strength) = settings.gravity_strength {
+        grid.gravity_strength = strength;
+    }
 
     let mut window: PistonWindow =
         WindowSettings::new("Particle sim", [window_width, window_height])
             .exit_on_esc(true)
-            .resizable(false)
+            .resizable(true)
             .samples(0)
             .vsync(true)
             .build()
             .unwrap();
 
-    let mut grid = Grid::new(GRID_WIDTH, GRID_HEIGHT);
+    let bindings = settings.bindings;
+    for warning in bindings.warnings() {
+        eprintln!("particle-sim: {}", warning);
+    }
+    // Every binding as a line of text for the help
+    let help_lines: Vec<String> = bindings
+        .help()
+        .into_iter()
+        .map(|(button, action)| format!("{}: {}", button, action.replace('_', " ")))
+        .collect();
+    let help_hint = match bindings
+        .help()
+        .into_iter()
+        .find(|(_, action)| action == "toggle_help")
+    {
+        Some((button, _)) => format!("{}: show the keys", button),
+        None => String::new(),
+    };
+    // Gravity to go back to when zero gravity gets turned off
+    let mut saved_gravity = grid.gravity;
+
+    let mut camera = Camera::new(
+        (sand_box_width, sand_box_height),
+        (grid.width, grid.height),
+        settings.cell_size,
+    );
+    camera.look_at_bottom();
+    // Which way WASD is moving the camera
+    let mut pan_direction: (f64, f64) = (0.0, 0.0);
+    // The middle mouse drags the camera around
+    let mut pan_held = false;
+    let mut ctrl_held = false;
 
-    let mut board_x: i32 = 0;
-    let mut board_y: i32 = 0;
     let mut mouse_held = false;
+    // Tools other than the brush draw from where the mouse was pressed to where it's let go
+    let mut tool = Tool::Brush;
+    let mut drag: Option<(Tool, (i32, i32))> = None;
+    // Holding shift draws a line whatever the tool is
+    let mut shift_held = false;
+    // Force fields get painted with the right mouse in the direction the mouse moves
+    let mut force_held = false;
+    let mut last_board_pos: (i32, i32) = (0, 0);
+    // Where the brush was last placed in the current stroke, so fast moves get filled in
+    let mut stroke_from: Option<(i32, i32)> = None;
+
+    let mut brush = Brush::new(BrushShape::Circle, 2);
+    // Spraying only places some of the cells under the brush, replacing draws over other elements
+    let mut spray = false;
+    let mut spray_density = SPRAY_DENSITY;
+    let mut replace = false;
+
+    // The simulation only runs when it isn't paused, but it can still be stepped
+    let mut paused = false;
+    // Worlds from before each change, the last one is the newest
+    let mut undo_history: Vec<Grid> = Vec::new();
+    let mut show_help = false;
+    // Shows what is in the cell under the mouse
+    let mut show_inspector = false;
+    let mut overlays = Overlays::default();
+    // Stats of the last ticks, the last one is the newest
+    let mut stats_history: VecDeque<Stats> = VecDeque::new();
+    let mut show_stats = false;
+
+    let update_interval = settings.update_interval;
+    let mut last_update = Instant::now();
 
-    let mut brush_size: i32 = 2;
+    let mut selected_element: u8 = settings.element;
+    // Element that new sources make, this is the last normal element that was picked
+    let mut source_element: u8 = WATER_CELL;
 
-    let update_interval = Duration::from_millis(5);
-    let mut last_update = Instant::now();
+    let mut mouse_x: f64 = 0.0;
+    let mut mouse_y: f64 = 0.0;
 
-    let mut selected_element: u8 = 1;
+    // The side panel buttons, they get made again when the window changes size
+    let wind_y = 70.0 + PALETTE.len() as f64 * 34.0 + 60.0;
+    let world_size_y = wind_y + 90.0;
+    let mut element_buttons = make_element_buttons(sand_box_width);
+    let mut wind_buttons = make_wind_buttons(sand_box_width, wind_y);
+    let mut world_size_buttons = make_world_size_buttons(sand_box_width, world_size_y);
 
     // Load font
     let assets = find_folder::Search::ParentsThenKids(3, 3)
@@ -64,147 +215,789 @@ fn main() {
 
     // Draw grid
     while let Some(event) = window.next() {
-        if let Some(Button::Keyboard(key)) = event.press_args() {
-            // Handle one time press actions
+        // Lay out the view and the side panel again for the new window size
+        if let Some(args) = event.resize_args() {
+            sand_box_width = (args.window_size[0] - PANEL_WIDTH).max(0.0);
+            sand_box_height = args.window_size[1];
+            camera.set_view_size(sand_box_width, sand_box_height);
+            element_buttons = make_element_buttons(sand_box_width);
+            wind_buttons = make_wind_buttons(sand_box_width, wind_y);
+            world_size_buttons = make_world_size_buttons(sand_box_width, world_size_y);
+        }
 
-            match key {
-                Key::D5 => selected_element = EMPTY_CELL,
-                Key::D1 => selected_element = SAND_CELL,
-                Key::D2 => selected_element = STEEL_CELL,
-                Key::D3 => selected_element = WATER_CELL,
-                Key::D4 => selected_element = FIRE_CELL,
-                _ => {}
-            }
+        if let Some(args) = event.update_args() {
+            camera.pan(
+                pan_direction.0 * PAN_SPEED * args.dt,
+                pan_direction.1 * PAN_SPEED * args.dt,
+            );
         }
 
         // Keeps track of mouse and board positions
         if let Some(cursor_pos) = event.mouse_cursor_args() {
-            let mouse_x: f64 = cursor_pos[0];
-            let mouse_y: f64 = cursor_pos[1];
+            if pan_held {
+                camera.pan(mouse_x - cursor_pos[0], mouse_y - cursor_pos[1]);
+            }
+            mouse_x = cursor_pos[0];
+            mouse_y = cursor_pos[1];
+        }
+        // The camera can move without the mouse moving, so this is worked out every event
+        let (board_x, board_y) = camera.screen_to_cell(mouse_x, mouse_y);
+        let on_board = camera.in_view(mouse_x, mouse_y);
+
+        if let Some(button) = event.press_args() {
+            // Handle one time press actions
+            if button == Button::Keyboard(Key::LCtrl) || button == Button::Keyboard(Key::RCtrl) {
+                ctrl_held = true;
+            }
+            if button == Button::Keyboard(Key::LShift) || button == Button::Keyboard(Key::RShift) {
+                shift_held = true;
+            }
+
+            // The side panel is always used with the left mouse
+            if button == Button::Mouse(MouseButton::Left) {
+                // Pick a element from the side panel
+                for (element, button) in &element_buttons {
+                    if button.is_clicked(mouse_x, mouse_y) {
+                        selected_element = *element;
+                    }
+                }
+
+                for (change, _, button) in &wind_buttons {
+                    if button.is_clicked(mouse_x, mouse_y) {
+                        grid.wind = match change {
+                            Some((change_x, change_y)) => {
+                                (grid.wind.0 + change_x, grid.wind.1 + change_y)
+                            }
+                            None => (0.0, 0.0),
+                        };
+                    }
+                }
+
+                // Grow or shrink the world, the floor stays where it is
+                for (change, _, button) in &world_size_buttons {
+                    if button.is_clicked(mouse_x, mouse_y) {
+                        let width = (grid.width + change * 2).clamp(MIN_WORLD_SIZE, MAX_WORLD_SIZE);
+                        let height = (grid.height + change).clamp(MIN_WORLD_SIZE, MAX_WORLD_SIZE);
+                        // Already as big or small as it goes, so there is nothing to undo
+                        if (width, height) == (grid.width, grid.height) {
+                            continue;
+                        }
+                        remember(&mut undo_history, &grid);
+                        grid.resize(width, height, Anchor::Bottom);
+                        camera.set_world_size(grid.width, grid.height);
+                    }
+                }
+            }
 
-            board_x = (mouse_x / CELL_SIZE) as i32;
-            board_y = (mouse_y / CELL_SIZE) as i32;
+            match bindings.action(button) {
+                // Every stroke can be undone on its own
+                Some(Action::Paint) if on_board => {
+                    remember(&mut undo_history, &grid);
+                    let active_tool = if shift_held { Tool::Line } else { tool };
+                    if active_tool.is_drag() {
+                        drag = Some((active_tool, (board_x, board_y)));
+                    } else if active_tool == Tool::Fill {
+                        grid.flood_fill(board_x, board_y, selected_element, source_element);
+                    } else {
+                        mouse_held = true;
+                    }
+                }
+                Some(Action::NextTool) => tool = tool.next(),
+                Some(Action::NextBrushShape) => brush.shape = brush.shape.next(),
+                Some(Action::ToggleSpray) => spray = !spray,
+                Some(Action::SprayThinner) => {
+                    spray_density = (spray_density - SPRAY_DENSITY_STEP).max(SPRAY_DENSITY_STEP)
+                }
+                Some(Action::SprayThicker) => {
+                    spray_density = (spray_density + SPRAY_DENSITY_STEP).min(1.0)
+                }
+                Some(Action::ToggleReplace) => replace = !replace,
+                Some(Action::PaintForce) if on_board => {
+                    remember(&mut undo_history, &grid);
+                    force_held = true;
+                }
+                Some(Action::DragCamera) => pan_held = true,
+                Some(Action::BrushBigger) => brush.size = (brush.size + 1).min(MAX_BRUSH_SIZE),
+                Some(Action::BrushSmaller) => brush.size = (brush.size - 1).max(1),
+                Some(Action::Pause) => paused = !paused,
+                Some(Action::Step) if paused => grid.update(),
+                Some(Action::Undo) => {
+                    if let Some(previous) = undo_history.pop() {
+                        grid = previous;
+                        camera.set_world_size(grid.width, grid.height);
+                    }
+                }
+                Some(Action::ToggleHelp) => show_help = !show_help,
+                Some(Action::ToggleInspector) => show_inspector = !show_inspector,
+                Some(Action::ToggleStats) => show_stats = !show_stats,
+                Some(Action::ToggleProcessedOverlay) => overlays.processed = !overlays.processed,
+                Some(Action::ToggleChangesOverlay) => overlays.changes = !overlays.changes,
+                Some(Action::CycleHeatMap) => {
+                    overlays.heat_map = match overlays.heat_map {
+                        None => Some(ScalarField::LifeTime),
+                        Some(field) => field.next(),
+                    }
+                }
+                Some(Action::SelectElement(element)) => selected_element = element,
+                Some(Action::ToggleRigidBodies) => grid.rigid_bodies = !grid.rigid_bodies,
+                Some(Action::ClearForces) => {
+                    remember(&mut undo_history, &grid);
+                    grid.clear_forces();
+                }
+                Some(Action::FlipGravity) => grid.gravity = (-grid.gravity.0, -grid.gravity.1),
+                Some(Action::TurnGravity) => grid.gravity = grid::rotate_direction(grid.gravity, 1),
+                Some(Action::CycleSideEdges) => {
+                    let mode = grid.boundaries.left.next();
+                    grid.boundaries.left = mode;
+                    grid.boundaries.right = mode;
+                }
+                Some(Action::CycleTopBottomEdges) => {
+                    let mode = grid.boundaries.top.next();
+                    grid.boundaries.top = mode;
+                    grid.boundaries.bottom = mode;
+                }
+                Some(Action::ToggleGravity) => {
+                    if grid.gravity == (0, 0) {
+                        grid.gravity = saved_gravity;
+                    } else {
+                        saved_gravity = grid.gravity;
+                        grid.gravity = (0, 0);
+                    }
+                }
+                Some(Action::SaveWorld) => {
+                    if let Err(error) = save_world(&grid, Path::new(WORLD_FILE)) {
+                        eprintln!("Could not save {}: {}", WORLD_FILE, error);
+                    }
+                }
+                Some(Action::LoadWorld) => match load_world(Path::new(WORLD_FILE)) {
+                    Ok(world) => {
+                        remember(&mut undo_history, &grid);
+                        grid = world;
+                        camera.set_world_size(grid.width, grid.height);
+                    }
+                    Err(error) => eprintln!("Could not load {}: {}", WORLD_FILE, error),
+                },
+                Some(Action::PanUp) => pan_direction.1 = -1.0,
+                Some(Action::PanDown) => pan_direction.1 = 1.0,
+                Some(Action::PanLeft) => pan_direction.0 = -1.0,
+                Some(Action::PanRight) => pan_direction.0 = 1.0,
+                _ => {}
+            }
         }
 
-        if let Some(Button::Mouse(MouseButton::Left)) = event.press_args() {
-            mouse_held = true;
+        if let Some(button) = event.release_args() {
+            if button == Button::Keyboard(Key::LCtrl) || button == Button::Keyboard(Key::RCtrl) {
+                ctrl_held = false;
+            }
+            if button == Button::Keyboard(Key::LShift) || button == Button::Keyboard(Key::RShift) {
+                shift_held = false;
+            }
+
+            match bindings.action(button) {
+                Some(Action::Paint) => {
+                    mouse_held = false;
+                    if let Some((drag_tool, start)) = drag.take() {
+                        let positions =
+                            drag_tool.positions(&grid, start, (board_x, board_y), brush);
+                        let placement = placement(spray, spray_density, replace);
+                        grid.place_positions(
+                            &positions,
+                            selected_element,
+                            source_element,
+                            placement,
+                        );
+                    }
+                }
+                Some(Action::PaintForce) => force_held = false,
+                Some(Action::DragCamera) => pan_held = false,
+                Some(Action::PanUp | Action::PanDown) => pan_direction.1 = 0.0,
+                Some(Action::PanLeft | Action::PanRight) => pan_direction.0 = 0.0,
+                _ => {}
+            }
         }
 
-        if let Some(Button::Mouse(MouseButton::Left)) = event.release_args() {
-            mouse_held = false;
+        if selected_element != EMPTY_CELL && !is_solid(selected_element) {
+            source_element = selected_element;
         }
 
         if let Some(scroll) = event.mouse_scroll_args() {
             let scroll_y = scroll[1]; // Vertical scroll
-            brush_size += scroll_y as i32;
-            brush_size = brush_size.max(1).min(10);
+            if ctrl_held {
+                camera.zoom_at(ZOOM_STEP.powf(scroll_y), mouse_x, mouse_y);
+            } else {
+                brush.size += scroll_y as i32;
+                brush.size = brush.size.clamp(1, MAX_BRUSH_SIZE);
+            }
         }
 
         // Place element
         if mouse_held
+            && on_board
+            && board_x >= 0
+            && board_x < grid.width as i32
+            && board_y >= 0
+            && board_y < grid.height as i32
+        {
+            // The mouse can move many cells between events, so the brush is stamped
+            // along the whole way it went
+            let from = stroke_from.unwrap_or((board_x, board_y));
+            let path = tools::line_positions(from, (board_x, board_y));
+            let positions = tools::stroke_positions(&grid, &path, brush);
+            let placement = placement(spray, spray_density, replace);
+            grid.place_positions(&positions, selected_element, source_element, placement);
+            stroke_from = Some((board_x, board_y));
+        } else {
+            stroke_from = None;
+        }
+
+        // Paint force fields
+        if force_held
+            && on_board
+            && (board_x, board_y) != last_board_pos
             && board_x >= 0
-            && board_x < GRID_WIDTH as i32
+            && board_x < grid.width as i32
             && board_y >= 0
-            && board_y < GRID_HEIGHT as i32
+            && board_y < grid.height as i32
         {
-            grid.place_element(board_x, board_y, selected_element, brush_size);
+            let move_x = (board_x - last_board_pos.0) as f32;
+            let move_y = (board_y - last_board_pos.1) as f32;
+            let length = (move_x * move_x + move_y * move_y).sqrt();
+            let force = (
+                move_x / length * FORCE_FIELD_STRENGTH,
+                move_y / length * FORCE_FIELD_STRENGTH,
+            );
+            for (x, y) in tools::line_positions(last_board_pos, (board_x, board_y)) {
+                grid.place_force(x, y, force, brush.size);
+            }
         }
+        last_board_pos = (board_x, board_y);
 
         // Update grid
-        if last_update.elapsed() >= update_interval {
+        if !paused && last_update.elapsed() >= update_interval {
             grid.update();
             last_update = Instant::now();
         }
 
+        // Stepping, undoing and loading change the tick too
+        if stats_history.back().map(|stats| stats.tick) != Some(grid.tick) {
+            if stats_history.len() >= STATS_HISTORY {
+                stats_history.pop_front();
+            }
+            stats_history.push_back(grid.stats());
+        }
+
         // Draw grid
         window.draw_2d(&event, |context, graphics, _device| {
+            // Cells the tool would draw on if the mouse was let go now
+            let preview = match drag {
+                Some((drag_tool, start)) => {
+                    drag_tool.positions(&grid, start, (board_x, board_y), brush)
+                }
+                None if on_board => {
+                    let hover_tool = if tool == Tool::Fill {
+                        tool
+                    } else {
+                        Tool::Brush
+                    };
+                    hover_tool.positions(&grid, (board_x, board_y), (board_x, board_y), brush)
+                }
+                None => Vec::new(),
+            };
+
             clear(LIGHT_BLUE_COLOR, graphics);
             draw_grid(
                 &grid,
-                CELL_SIZE,
-                board_x,
-                board_y,
+                &camera,
+                &preview,
                 selected_element,
-                brush_size,
+                &overlays,
                 &context,
                 graphics,
             );
 
+            // Cells that are partly in view can reach past it, so the panel covers them
+            rectangle(
+                LIGHT_BLUE_COLOR,
+                [sand_box_width, 0.0, PANEL_WIDTH, sand_box_height],
+                context.transform,
+                graphics,
+            );
+
             // Draw text
-            let current = match selected_element {
-                EMPTY_CELL => "Eraser",
-                SAND_CELL => "Sand",
-                STEEL_CELL => "Steel",
-                WATER_CELL => "Water",
-                FIRE_CELL => "Fire",
-                _ => "<element>",
+            let current = if selected_element == SOURCE_CELL {
+                format!("Source: {}", element_name(source_element))
+            } else {
+                format!("Current: {}", element_name(selected_element))
             };
+            let current_brush = Label::new(sand_box_width + 10.0, 25.0, current);
 
-            let current_brush = Label::new(
-                sand_box_width as f64 + 10.0,
-                25.0,
-                format!("Current: {}", current),
-            );
-
-            let brush_size = Label::new(
-                sand_box_width as f64 + 10.0,
+            let brush_label = Label::new(
+                sand_box_width + 10.0,
                 50.0,
-                format!("Brush size: {}", brush_size),
+                format!("Brush: {} {}", brush.size, brush.shape.name()),
             );
 
+            let rigid_bodies = Label::new(
+                sand_box_width + 10.0,
+                70.0 + PALETTE.len() as f64 * 34.0 + 20.0,
+                format!(
+                    "Rigid bodies: {}",
+                    if grid.rigid_bodies { "on" } else { "off" }
+                ),
+            )
+            .with_font_size(16);
+
+            let gravity = Label::new(
+                sand_box_width + 10.0,
+                70.0 + PALETTE.len() as f64 * 34.0 + 40.0,
+                if grid.gravity_strength == 1.0 || grid.gravity == (0, 0) {
+                    format!("Gravity: {}", gravity_name(grid.gravity))
+                } else {
+                    format!(
+                        "Gravity: {} x{:.1}",
+                        gravity_name(grid.gravity),
+                        grid.gravity_strength
+                    )
+                },
+            )
+            .with_font_size(16);
+
+            let wind = Label::new(
+                sand_box_width + 10.0,
+                wind_y,
+                format!("Wind: {:.1}, {:.1}", grid.wind.0, grid.wind.1),
+            )
+            .with_font_size(16);
+
+            let edges = Label::new(
+                sand_box_width + 10.0,
+                wind_y + 65.0,
+                format!(
+                    "Sides: {}, Top/bottom: {}",
+                    grid.boundaries.left, grid.boundaries.top
+                ),
+            )
+            .with_font_size(16);
+
+            let world_size = Label::new(
+                sand_box_width + 10.0,
+                world_size_y,
+                format!("World: {}x{}", grid.width, grid.height),
+            )
+            .with_font_size(16);
+
+            let tool_label = Label::new(
+                sand_box_width + 10.0,
+                world_size_y + 60.0,
+                format!("Tool: {}", tool.name()),
+            )
+            .with_font_size(16);
+
+            let modes = Label::new(
+                sand_box_width + 10.0,
+                world_size_y + 80.0,
+                format!(
+                    "Spray: {}, Replace: {}",
+                    if spray {
+                        format!("{:.0}%", spray_density * 100.0)
+                    } else {
+                        "off".to_string()
+                    },
+                    if replace { "on" } else { "off" }
+                ),
+            )
+            .with_font_size(16);
+
+            let status = Label::new(
+                sand_box_width + 10.0,
+                world_size_y + 100.0,
+                if paused {
+                    "Paused".to_string()
+                } else {
+                    help_hint.clone()
+                },
+            )
+            .with_font_size(16);
+
             current_brush.draw(&context, graphics, &mut glyphs);
-            brush_size.draw(&context, graphics, &mut glyphs);
+            brush_label.draw(&context, graphics, &mut glyphs);
+            rigid_bodies.draw(&context, graphics, &mut glyphs);
+            gravity.draw(&context, graphics, &mut glyphs);
+            wind.draw(&context, graphics, &mut glyphs);
+            edges.draw(&context, graphics, &mut glyphs);
+            world_size.draw(&context, graphics, &mut glyphs);
+            tool_label.draw(&context, graphics, &mut glyphs);
+            modes.draw(&context, graphics, &mut glyphs);
+            if let Some(field) = overlays.heat_map {
+                let heat_map = Label::new(
+                    sand_box_width + 10.0,
+                    world_size_y + 120.0,
+                    format!("Heat map: {}", field.name()),
+                )
+                .with_font_size(16);
+                heat_map.draw(&context, graphics, &mut glyphs);
+            }
+            status.draw(&context, graphics, &mut glyphs);
+
+            // Draw wind buttons
+            for (index, (_, text, button)) in wind_buttons.iter().enumerate() {
+                button.draw(
+                    &context,
+                    graphics,
+                    button.is_hovered(mouse_x, mouse_y),
+                    false,
+                );
+
+                let label = Label::new(
+                    sand_box_width + 20.0 + index as f64 * 36.0,
+                    wind_y + 30.0,
+                    text.to_string(),
+                )
+                .with_font_size(16);
+                label.draw(&context, graphics, &mut glyphs);
+            }
+
+            // Draw world size buttons
+            for (index, (_, text, button)) in world_size_buttons.iter().enumerate() {
+                button.draw(
+                    &context,
+                    graphics,
+                    button.is_hovered(mouse_x, mouse_y),
+                    false,
+                );
+
+                let label = Label::new(
+                    sand_box_width + 20.0 + index as f64 * 36.0,
+                    world_size_y + 30.0,
+                    text.to_string(),
+                )
+                .with_font_size(16);
+                label.draw(&context, graphics, &mut glyphs);
+            }
+
+            // Draw element buttons
+            for (index, (element, button)) in element_buttons.iter().enumerate() {
+                button.draw(
+                    &context,
+                    graphics,
+                    button.is_hovered(mouse_x, mouse_y),
+                    *element == selected_element,
+                );
+
+                let label = Label::new(
+                    sand_box_width + 18.0,
+                    90.0 + index as f64 * 34.0,
+                    button.label.clone(),
+                )
+                .with_font_size(16);
+                label.draw(&context, graphics, &mut glyphs);
+            }
+
+            // The inspector floats next to the mouse so it doesn't hide the cell
+            if show_inspector
+                && on_board
+                && let Some(lines) = grid.inspect(board_x, board_y)
+            {
+                let tooltip =
+                    Tooltip::new(mouse_x + 16.0, mouse_y + 16.0, lines, HELP_BACKGROUND_COLOR);
+                tooltip.draw(
+                    (sand_box_width, sand_box_height),
+                    &context,
+                    graphics,
+                    &mut glyphs,
+                );
+            }
+
+            // The stats cover the side panel with the counts of the last ticks
+            if show_stats && let Some(stats) = stats_history.back() {
+                rectangle(
+                    HELP_BACKGROUND_COLOR,
+                    [sand_box_width, 0.0, PANEL_WIDTH, sand_box_height],
+                    context.transform,
+                    graphics,
+                );
+                draw_stats(
+                    stats,
+                    &stats_history,
+                    sand_box_width + 10.0,
+                    &context,
+                    graphics,
+                    &mut glyphs,
+                );
+            }
+
+            // The help covers the side panel with every binding
+            if show_help {
+                rectangle(
+                    HELP_BACKGROUND_COLOR,
+                    [sand_box_width, 0.0, PANEL_WIDTH, sand_box_height],
+                    context.transform,
+                    graphics,
+                );
+                for (index, line) in help_lines.iter().enumerate() {
+                    let label = Label::new(
+                        sand_box_width + 10.0,
+                        20.0 + index as f64 * 18.0,
+                        line.clone(),
+                    )
+                    .with_font_size(13);
+                    label.draw(&context, graphics, &mut glyphs);
+                }
+            }
         });
     }
 }
 
+// One button for each element in the palette
+fn make_element_buttons(panel_x: f64) -> Vec<(u8, UIButton)> {
+    PALETTE
+        .iter()
+        .enumerate()
+        .map(|(index, &element)| {
+            let button = UIButton::new(
+                panel_x + 10.0,
+                70.0 + index as f64 * 34.0,
+                180.0,
+                28.0,
+                element_name(element),
+                element_color(element),
+            );
+            (element, button)
+        })
+        .collect()
+}
+
+// Buttons that change the wind, the last one turns it off
+fn make_wind_buttons(panel_x: f64, wind_y: f64) -> Vec<(WindChange, &'static str, UIButton)> {
+    [
+        (Some((-WIND_STEP, 0.0)), "<"),
+        (Some((WIND_STEP, 0.0)), ">"),
+        (Some((0.0, -WIND_STEP)), "^"),
+        (Some((0.0, WIND_STEP)), "v"),
+        (None, "0"),
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(index, (change, text))| {
+        let button = UIButton::new(
+            panel_x + 10.0 + index as f64 * 36.0,
+            wind_y + 10.0,
+            32.0,
+            28.0,
+            text,
+            LIGHT_BLUE_COLOR,
+        );
+        (change, text, button)
+    })
+    .collect()
+}
+
+// Buttons that make the world smaller or bigger
+fn make_world_size_buttons(panel_x: f64, world_size_y: f64) -> Vec<(i64, &'static str, UIButton)> {
+    [(-WORLD_SIZE_STEP, "-"), (WORLD_SIZE_STEP, "+")]
+        .into_iter()
+        .enumerate()
+        .map(|(index, (change, text))| {
+            let button = UIButton::new(
+                panel_x + 10.0 + index as f64 * 36.0,
+                world_size_y + 10.0,
+                32.0,
+                28.0,
+                text,
+                LIGHT_BLUE_COLOR,
+            );
+            (change, text, button)
+        })
+        .collect()
+}
+
+// Tick, update time and the count of every element with graphs of how they changed
+fn draw_stats<G, C>(
+    stats: &Stats,
+    history: &VecDeque<Stats>,
+    x: f64,
+    context: &Context,
+    graphics: &mut G,
+    glyphs: &mut C,
+) where
+    G: Graphics<Texture = C::Texture>,
+    C: character::CharacterCache,
+{
+    let graph_width = PANEL_WIDTH - 20.0;
+    let update_times: Vec<f64> = history
+        .iter()
+        .map(|stats| stats.update_time.as_secs_f64())
+        .collect();
+    let particles: Vec<f64> = history
+        .iter()
+        .map(|stats| stats.particles() as f64)
+        .collect();
+
+    Label::new(x, 20.0, format!("Tick: {}", stats.tick))
+        .with_font_size(16)
+        .draw(context, graphics, glyphs);
+
+    Label::new(
+        x,
+        42.0,
+        format!("Update: {:.2} ms", stats.update_time.as_secs_f64() * 1000.0),
+    )
+    .with_font_size(13)
+    .draw(context, graphics, glyphs);
+    Graph::new(x, 48.0, graph_width, 30.0, SPARK_COLOR).draw(&update_times, context, graphics);
+
+    Label::new(x, 98.0, format!("Particles: {}", stats.particles()))
+        .with_font_size(13)
+        .draw(context, graphics, glyphs);
+    Graph::new(x, 104.0, graph_width, 30.0, WHITE_COLOR).draw(&particles, context, graphics);
+
+    // Only elements that were in the world some time in the history
+    let mut y = 154.0;
+    for cell_type in 1..ELEMENT_COUNT {
+        let counts: Vec<f64> = history
+            .iter()
+            .map(|stats| stats.count(cell_type) as f64)
+            .collect();
+        if counts.iter().all(|&count| count == 0.0) {
+            continue;
+        }
+
+        Label::new(
+            x,
+            y,
+            format!("{}: {}", element_name(cell_type), stats.count(cell_type)),
+        )
+        .with_font_size(13)
+        .draw(context, graphics, glyphs);
+        Graph::new(
+            x + 110.0,
+            y - 11.0,
+            graph_width - 110.0,
+            12.0,
+            element_color(cell_type),
+        )
+        .draw(&counts, context, graphics);
+        y += 18.0;
+    }
+}
+
+// Debug views drawn on top of the world
+#[derive(Default)]
+struct Overlays {
+    // Cells that were moved or updated last tick
+    processed: bool,
+    // Chunks where a cell turned into something else last tick
+    changes: bool,
+    heat_map: Option<ScalarField>,
+}
+
+// How the brush and tools put elements down
+fn placement(spray: bool, spray_density: f64, replace: bool) -> Placement {
+    Placement {
+        density: if spray { spray_density } else { 1.0 },
+        replace,
+    }
+}
+
+// Saves a copy of the world so the next change can be undone
+fn remember(undo_history: &mut Vec<Grid>, grid: &Grid) {
+    if undo_history.len() >= UNDO_LIMIT {
+        undo_history.remove(0);
+    }
+    undo_history.push(grid.clone());
+}
+
+// Name of the way gravity pulls, as shown in the side panel
+fn gravity_name(gravity: (i64, i64)) -> &'static str {
+    match gravity {
+        (0, 1) => "down",
+        (0, -1) => "up",
+        (-1, 0) => "left",
+        (1, 0) => "right",
+        (-1, 1) => "down left",
+        (1, 1) => "down right",
+        (-1, -1) => "up left",
+        (1, -1) => "up right",
+        _ => "off",
+    }
+}
+
 fn draw_grid<G: Graphics>(
     grid: &Grid,
-    cell_size: f64,
-    board_x: i32,
-    board_y: i32,
+    camera: &Camera,
+    preview: &[(i32, i32)],
     selected_element: u8,
-    brush_size: i32,
+    overlays: &Overlays,
     context: &Context,
     graphics: &mut G,
 ) {
-    let mouse_hover = grid.get_circle_positions(board_x, board_y, brush_size);
+    let cell_size = camera.zoom;
 
-    // Draw simple grid
-    for y in 0..grid.height {
-        for x in 0..grid.width {
-            let x_pos = x as f64 * cell_size;
-            let y_pos = y as f64 * cell_size;
+    // Draw simple grid, only the cells the camera can see
+    let ((start_x, end_x), (start_y, end_y)) = camera.visible_cells();
+    for y in start_y..end_y {
+        for x in start_x..end_x {
+            let (x_pos, y_pos) = camera.cell_to_screen(x, y);
 
             let cell_rect: [f64; 4] = [x_pos, y_pos, cell_size, cell_size];
 
+            // Tint cells that have a force field painted on them
+            if grid.force_field[y as usize][x as usize] != (0.0, 0.0) {
+                rectangle(FORCE_FIELD_COLOR, cell_rect, context.transform, graphics);
+            }
+
             // Don't render if cell is empty
             if grid.grid[y as usize][x as usize].cell_type == 0 {
                 continue;
             }
 
-            let color = grid.grid[y as usize][x as usize].cell_color;
+            // Charged conductors light up
+            let color =
+                if let Some(value) = overlays.heat_map.and_then(|field| field.value(grid, x, y)) {
+                    false_color(value)
+                } else if grid.charge[y as usize][x as usize] == grid::CHARGE_HEAD {
+                    SPARK_COLOR
+                } else {
+                    grid.grid[y as usize][x as usize].cell_color
+                };
 
             rectangle(color, cell_rect, context.transform, graphics);
+
+            if overlays.processed && grid.is_processed(x, y) {
+                rectangle(
+                    PROCESSED_OVERLAY_COLOR,
+                    cell_rect,
+                    context.transform,
+                    graphics,
+                );
+            }
+        }
+    }
+
+    // Outline the chunks that changed, only the ones that can be seen
+    if overlays.changes {
+        let chunk_pixels = CHUNK_SIZE as f64 * cell_size;
+        for (chunk_x, chunk_y) in grid.changed_chunks() {
+            let (x, y) = (chunk_x * CHUNK_SIZE, chunk_y * CHUNK_SIZE);
+            if x + CHUNK_SIZE <= start_x || x >= end_x || y + CHUNK_SIZE <= start_y || y >= end_y {
+                continue;
+            }
+            let (x_pos, y_pos) = camera.cell_to_screen(x, y);
+            rectangle::Rectangle::new_border(CHANGED_CHUNK_COLOR, 1.0).draw(
+                [x_pos, y_pos, chunk_pixels, chunk_pixels],
+                &context.draw_state,
+                context.transform,
+                graphics,
+            );
         }
     }
 
-    // Draw mouse hover
-    for (x, y) in mouse_hover {
-        let x_pos = x as f64 * cell_size;
-        let y_pos = y as f64 * cell_size;
+    // Draw what the tool is about to draw
+    for &(x, y) in preview {
+        let (x_pos, y_pos) = camera.cell_to_screen(x as i64, y as i64);
         let cell_rect: [f64; 4] = [x_pos, y_pos, cell_size, cell_size];
 
-        let color: [f32; 4] = match selected_element {
-            SAND_CELL => SAND_COLOR,
-            STEEL_CELL => STEEL_COLOR,
-            WATER_CELL => WATER_COLOR,
-            FIRE_CELL => FIRE_COLOR,
-            SMOKE_CELL => SMOKE_COLOR,
-            STEAM_CELL => STEAM_COLOR,
-            EMPTY_CELL => LIGHT_BLUE_COLOR,
-            _ => TRANSPAERNT_COLOR,
-        };
+        let color: [f32; 4] = element_color(selected_element);
 
         rectangle(color, cell_rect, context.transform, graphics);
     }
@@ -0,0 +1,59 @@
+use crate::cell::*;
+use crate::grid::{CHARGE_HEAD, Grid, MAX_VELOCITY};
+
+// Numbers every cell has that can be shown as a heat map
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScalarField {
+    // How far along its life the cell is, for cells that burn out or evaporate
+    LifeTime,
+    Density,
+    Speed,
+    Charge,
+}
+
+impl ScalarField {
+    // The next field, None after the last one so cycling can turn the heat map off
+    pub fn next(self) -> Option<Self> {
+        match self {
+            ScalarField::LifeTime => Some(ScalarField::Density),
+            ScalarField::Density => Some(ScalarField::Speed),
+            ScalarField::Speed => Some(ScalarField::Charge),
+            ScalarField::Charge => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ScalarField::LifeTime => "Life time",
+            ScalarField::Density => "Density",
+            ScalarField::Speed => "Speed",
+            ScalarField::Charge => "Charge",
+        }
+    }
+
+    // Value of the field at the giving cords between 0 and 1, empty cells don't have one
+    pub fn value(self, grid: &Grid, x: i64, y: i64) -> Option<f32> {
+        let cell = &grid.grid[y as usize][x as usize];
+        if cell.cell_type == EMPTY_CELL {
+            return None;
+        }
+
+        let value = match self {
+            ScalarField::LifeTime if cell.max_life_time == 0 => 0.0,
+            ScalarField::LifeTime => cell.life_time as f32 / cell.max_life_time as f32,
+            ScalarField::Density => {
+                // Compared to the heaviest element so every element fits in
+                let heaviest = (0..ELEMENT_COUNT).map(density).fold(0.0, f32::max);
+                density(cell.cell_type) / heaviest
+            }
+            ScalarField::Speed => {
+                let speed =
+                    (cell.velocity_x * cell.velocity_x + cell.velocity_y * cell.velocity_y).sqrt();
+                speed / MAX_VELOCITY
+            }
+            ScalarField::Charge => grid.charge[y as usize][x as usize] as f32 / CHARGE_HEAD as f32,
+        };
+
+        Some(value.clamp(0.0, 1.0))
+    }
+}
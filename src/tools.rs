@@ -0,0 +1,231 @@
+use crate::grid::Grid;
+use std::collections::HashSet;
+
+// Shape the brush paints around the mouse
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BrushShape {
+    Circle,
+    Square,
+    // Flat line as wide as a circle of the same size
+    Line,
+}
+
+impl BrushShape {
+    // The next shape, used to cycle through them
+    pub fn next(self) -> Self {
+        match self {
+            BrushShape::Circle => BrushShape::Square,
+            BrushShape::Square => BrushShape::Line,
+            BrushShape::Line => BrushShape::Circle,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BrushShape::Circle => "Circle",
+            BrushShape::Square => "Square",
+            BrushShape::Line => "Line",
+        }
+    }
+}
+
+// What the brush looks like, size is how far it reaches from the middle
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Brush {
+    pub shape: BrushShape,
+    pub size: i32,
+}
+
+impl Brush {
+    pub fn new(shape: BrushShape, size: i32) -> Self {
+        Brush { shape, size }
+    }
+
+    // Cells the brush covers with its middle on the giving cell, only the ones inside the grid
+    pub fn positions(self, grid: &Grid, x: i32, y: i32) -> Vec<(i32, i32)> {
+        let size = self.size;
+        let positions = match self.shape {
+            BrushShape::Circle => return grid.get_circle_positions(x, y, size),
+            BrushShape::Square => {
+                rectangle_positions((x - size, y - size), (x + size, y + size), true)
+            }
+            BrushShape::Line => line_positions((x - size, y), (x + size, y)),
+        };
+
+        positions
+            .into_iter()
+            .filter(|&(x, y)| x >= 0 && y >= 0 && x < grid.width as i32 && y < grid.height as i32)
+            .collect()
+    }
+}
+
+// Ways of drawing elements into the world
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tool {
+    // Paints the brush under the mouse
+    Brush,
+    // Straight line from where the drag started, drawn with the brush
+    Line,
+    Rectangle,
+    FilledRectangle,
+    // Ellipse that fits in the box of the drag
+    Ellipse,
+    FilledEllipse,
+    // Fills every connected cell that is the same element as the one clicked
+    Fill,
+}
+
+impl Tool {
+    // The next tool, used to cycle through them
+    pub fn next(self) -> Self {
+        match self {
+            Tool::Brush => Tool::Line,
+            Tool::Line => Tool::Rectangle,
+            Tool::Rectangle => Tool::FilledRectangle,
+            Tool::FilledRectangle => Tool::Ellipse,
+            Tool::Ellipse => Tool::FilledEllipse,
+            Tool::FilledEllipse => Tool::Fill,
+            Tool::Fill => Tool::Brush,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tool::Brush => "Brush",
+            Tool::Line => "Line",
+            Tool::Rectangle => "Rectangle",
+            Tool::FilledRectangle => "Filled rectangle",
+            Tool::Ellipse => "Ellipse",
+            Tool::FilledEllipse => "Filled ellipse",
+            Tool::Fill => "Fill",
+        }
+    }
+
+    // If the tool draws from where the mouse was pressed to where it was let go
+    pub fn is_drag(self) -> bool {
+        !matches!(self, Tool::Brush | Tool::Fill)
+    }
+
+    // Cells the tool covers when dragged from start to end, only the ones inside the grid
+    //
+    // The brush and fill only care about the end
+    pub fn positions(
+        self,
+        grid: &Grid,
+        start: (i32, i32),
+        end: (i32, i32),
+        brush: Brush,
+    ) -> Vec<(i32, i32)> {
+        let positions = match self {
+            Tool::Brush => brush.positions(grid, end.0, end.1),
+            Tool::Line => stroke_positions(grid, &line_positions(start, end), brush),
+            Tool::Rectangle => rectangle_positions(start, end, false),
+            Tool::FilledRectangle => rectangle_positions(start, end, true),
+            Tool::Ellipse => ellipse_positions(start, end, false),
+            Tool::FilledEllipse => ellipse_positions(start, end, true),
+            Tool::Fill => grid.flood_fill_positions(end.0, end.1),
+        };
+
+        positions
+            .into_iter()
+            .filter(|&(x, y)| x >= 0 && y >= 0 && x < grid.width as i32 && y < grid.height as i32)
+            .collect()
+    }
+}
+
+// Cells on a straight line between two cells, both ends included
+//
+// Uses Bresenham's line algorithm so there are no gaps and no doubled up cells
+pub fn line_positions(start: (i32, i32), end: (i32, i32)) -> Vec<(i32, i32)> {
+    let (mut x, mut y) = start;
+    let dx = (end.0 - x).abs();
+    let dy = -(end.1 - y).abs();
+    let step_x = if x < end.0 { 1 } else { -1 };
+    let step_y = if y < end.1 { 1 } else { -1 };
+    let mut error = dx + dy;
+
+    let mut positions = vec![(x, y)];
+    while (x, y) != end {
+        let doubled = error * 2;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+        positions.push((x, y));
+    }
+
+    positions
+}
+
+// Cells of a rectangle with the giving corners, only the edge if it isn't filled
+pub fn rectangle_positions(corner: (i32, i32), other: (i32, i32), filled: bool) -> Vec<(i32, i32)> {
+    let (left, right) = (corner.0.min(other.0), corner.0.max(other.0));
+    let (top, bottom) = (corner.1.min(other.1), corner.1.max(other.1));
+
+    let mut positions = Vec::new();
+    for y in top..=bottom {
+        for x in left..=right {
+            let on_edge = x == left || x == right || y == top || y == bottom;
+            if filled || on_edge {
+                positions.push((x, y));
+            }
+        }
+    }
+
+    positions
+}
+
+// Cells of the biggest ellipse that fits in the rectangle with the giving corners,
+// only the outline if it isn't filled
+pub fn ellipse_positions(corner: (i32, i32), other: (i32, i32), filled: bool) -> Vec<(i32, i32)> {
+    let (left, right) = (corner.0.min(other.0), corner.0.max(other.0));
+    let (top, bottom) = (corner.1.min(other.1), corner.1.max(other.1));
+    let center_x = (left + right) as f64 / 2.0;
+    let center_y = (top + bottom) as f64 / 2.0;
+    // Half a cell bigger so the ellipse reaches the edges of the rectangle
+    let radius_x = (right - left) as f64 / 2.0 + 0.5;
+    let radius_y = (bottom - top) as f64 / 2.0 + 0.5;
+
+    let inside = |x: i32, y: i32| {
+        let nx = (x as f64 - center_x) / radius_x;
+        let ny = (y as f64 - center_y) / radius_y;
+        nx * nx + ny * ny <= 1.0
+    };
+
+    let mut positions = Vec::new();
+    for y in top..=bottom {
+        for x in left..=right {
+            if !inside(x, y) {
+                continue;
+            }
+            // A cell is on the outline if one of its sides touches the outside
+            let on_edge = [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+                .into_iter()
+                .any(|(nx, ny)| !inside(nx, ny));
+            if filled || on_edge {
+                positions.push((x, y));
+            }
+        }
+    }
+
+    positions
+}
+
+// Cells the brush covers when it is moved along the path
+pub fn stroke_positions(grid: &Grid, path: &[(i32, i32)], brush: Brush) -> Vec<(i32, i32)> {
+    let mut seen = HashSet::new();
+    let mut positions = Vec::new();
+    for &(x, y) in path {
+        for position in brush.positions(grid, x, y) {
+            if seen.insert(position) {
+                positions.push(position);
+            }
+        }
+    }
+
+    positions
+}
@@ -0,0 +1,120 @@
+// Smallest and biggest a cell can be drawn, in pixels
+pub const MIN_ZOOM: f64 = 2.0;
+pub const MAX_ZOOM: f64 = 32.0;
+
+// The part of the world that is shown in the window
+pub struct Camera {
+    // World cords of the top left corner of the view, in cells
+    pub x: f64,
+    pub y: f64,
+    // How many pixels wide a cell is drawn
+    pub zoom: f64,
+    // Size of the view on the screen, in pixels
+    pub view_width: f64,
+    pub view_height: f64,
+    // Size of the world, in cells
+    world_width: f64,
+    world_height: f64,
+}
+
+impl Camera {
+    pub fn new(
+        (view_width, view_height): (f64, f64),
+        (world_width, world_height): (i64, i64),
+        zoom: f64,
+    ) -> Self {
+        Camera {
+            x: 0.0,
+            y: 0.0,
+            zoom: zoom.clamp(MIN_ZOOM, MAX_ZOOM),
+            view_width,
+            view_height,
+            world_width: world_width as f64,
+            world_height: world_height as f64,
+        }
+    }
+
+    // Changes how big the view is on the screen, for when the window gets resized
+    pub fn set_view_size(&mut self, view_width: f64, view_height: f64) {
+        self.view_width = view_width;
+        self.view_height = view_height;
+        self.clamp();
+    }
+
+    // Changes how big the world is, for when the grid gets resized or loaded
+    pub fn set_world_size(&mut self, world_width: i64, world_height: i64) {
+        self.world_width = world_width as f64;
+        self.world_height = world_height as f64;
+        self.clamp();
+    }
+
+    // Points the camera at the bottom middle of the world, where things end up falling to
+    pub fn look_at_bottom(&mut self) {
+        self.x = (self.world_width - self.view_width / self.zoom) / 2.0;
+        self.y = self.world_height;
+        self.clamp();
+    }
+
+    // If the screen cords are inside the view
+    pub fn in_view(&self, screen_x: f64, screen_y: f64) -> bool {
+        screen_x >= 0.0
+            && screen_y >= 0.0
+            && screen_x < self.view_width
+            && screen_y < self.view_height
+    }
+
+    // Cell under the giving screen cords
+    pub fn screen_to_cell(&self, screen_x: f64, screen_y: f64) -> (i32, i32) {
+        (
+            (self.x + screen_x / self.zoom).floor() as i32,
+            (self.y + screen_y / self.zoom).floor() as i32,
+        )
+    }
+
+    // Screen cords of the top left corner of a cell
+    pub fn cell_to_screen(&self, x: i64, y: i64) -> (f64, f64) {
+        (
+            (x as f64 - self.x) * self.zoom,
+            (y as f64 - self.y) * self.zoom,
+        )
+    }
+
+    // First and last cells that can be seen, the end is not included
+    pub fn visible_cells(&self) -> ((i64, i64), (i64, i64)) {
+        let start_x = self.x.floor().max(0.0) as i64;
+        let start_y = self.y.floor().max(0.0) as i64;
+        let end_x = (self.x + self.view_width / self.zoom)
+            .ceil()
+            .min(self.world_width) as i64;
+        let end_y = (self.y + self.view_height / self.zoom)
+            .ceil()
+            .min(self.world_height) as i64;
+        ((start_x, end_x), (start_y, end_y))
+    }
+
+    // Moves the camera by a amount of pixels
+    pub fn pan(&mut self, pixels_x: f64, pixels_y: f64) {
+        self.x += pixels_x / self.zoom;
+        self.y += pixels_y / self.zoom;
+        self.clamp();
+    }
+
+    // Zooms in or out while keeping the cell under the screen cords in the same place
+    pub fn zoom_at(&mut self, factor: f64, screen_x: f64, screen_y: f64) {
+        let world_x = self.x + screen_x / self.zoom;
+        let world_y = self.y + screen_y / self.zoom;
+
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.x = world_x - screen_x / self.zoom;
+        self.y = world_y - screen_y / self.zoom;
+        self.clamp();
+    }
+
+    // Keeps the view inside the world, a world smaller than the view sits in the top left
+    fn clamp(&mut self) {
+        let max_x = (self.world_width - self.view_width / self.zoom).max(0.0);
+        let max_y = (self.world_height - self.view_height / self.zoom).max(0.0);
+        self.x = self.x.clamp(0.0, max_x);
+        self.y = self.y.clamp(0.0, max_y);
+    }
+}
@@ -0,0 +1,54 @@
+use piston_window::graphics::{Context, Graphics, rectangle};
+
+// Small bar graph of how a number changed over time
+pub struct Graph {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    color: [f32; 4],
+}
+
+impl Graph {
+    pub fn new(x: f64, y: f64, width: f64, height: f64, color: [f32; 4]) -> Self {
+        Graph {
+            x,
+            y,
+            width,
+            height,
+            color,
+        }
+    }
+
+    // Draws the values oldest first, the biggest one reaches the top
+    // Only the newest values that fit get drawn, one pixel each
+    pub fn draw<G: Graphics>(&self, values: &[f64], context: &Context, graphics: &mut G) {
+        rectangle(
+            [0.0, 0.0, 0.0, 0.5],
+            [self.x, self.y, self.width, self.height],
+            context.transform,
+            graphics,
+        );
+
+        let shown = &values[values.len().saturating_sub(self.width as usize)..];
+        let biggest = shown.iter().copied().fold(0.0, f64::max);
+        if biggest <= 0.0 {
+            return;
+        }
+
+        for (index, value) in shown.iter().enumerate() {
+            let bar_height = value / biggest * self.height;
+            rectangle(
+                self.color,
+                [
+                    self.x + index as f64,
+                    self.y + self.height - bar_height,
+                    1.0,
+                    bar_height,
+                ],
+                context.transform,
+                graphics,
+            );
+        }
+    }
+}
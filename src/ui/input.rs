@@ -0,0 +1,415 @@
+use std::collections::HashMap;
+
+use particle_sim::cell::*;
+use piston_window::{Button, Key, MouseButton};
+
+// Something a key or mouse button can be bound to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    SelectElement(u8),
+    Paint,
+    NextTool,
+    NextBrushShape,
+    ToggleSpray,
+    SprayThinner,
+    SprayThicker,
+    ToggleReplace,
+    PaintForce,
+    DragCamera,
+    BrushBigger,
+    BrushSmaller,
+    Pause,
+    Step,
+    Undo,
+    ToggleHelp,
+    ToggleInspector,
+    ToggleStats,
+    ToggleProcessedOverlay,
+    ToggleChangesOverlay,
+    CycleHeatMap,
+    ToggleRigidBodies,
+    ClearForces,
+    FlipGravity,
+    TurnGravity,
+    ToggleGravity,
+    CycleSideEdges,
+    CycleTopBottomEdges,
+    SaveWorld,
+    LoadWorld,
+    PanUp,
+    PanDown,
+    PanLeft,
+    PanRight,
+}
+
+// Every action but selecting a element with the name it has in the config file,
+// in the order the help shows them
+const ACTIONS: [(&str, Action); 33] = [
+    ("paint", Action::Paint),
+    ("next_tool", Action::NextTool),
+    ("next_brush_shape", Action::NextBrushShape),
+    ("toggle_spray", Action::ToggleSpray),
+    ("spray_thinner", Action::SprayThinner),
+    ("spray_thicker", Action::SprayThicker),
+    ("toggle_replace", Action::ToggleReplace),
+    ("paint_force", Action::PaintForce),
+    ("drag_camera", Action::DragCamera),
+    ("brush_bigger", Action::BrushBigger),
+    ("brush_smaller", Action::BrushSmaller),
+    ("pause", Action::Pause),
+    ("step", Action::Step),
+    ("undo", Action::Undo),
+    ("toggle_help", Action::ToggleHelp),
+    ("toggle_inspector", Action::ToggleInspector),
+    ("toggle_stats", Action::ToggleStats),
+    ("toggle_processed_overlay", Action::ToggleProcessedOverlay),
+    ("toggle_changes_overlay", Action::ToggleChangesOverlay),
+    ("cycle_heat_map", Action::CycleHeatMap),
+    ("toggle_rigid_bodies", Action::ToggleRigidBodies),
+    ("clear_forces", Action::ClearForces),
+    ("flip_gravity", Action::FlipGravity),
+    ("turn_gravity", Action::TurnGravity),
+    ("toggle_gravity", Action::ToggleGravity),
+    ("cycle_side_edges", Action::CycleSideEdges),
+    ("cycle_top_bottom_edges", Action::CycleTopBottomEdges),
+    ("save_world", Action::SaveWorld),
+    ("load_world", Action::LoadWorld),
+    ("pan_up", Action::PanUp),
+    ("pan_down", Action::PanDown),
+    ("pan_left", Action::PanLeft),
+    ("pan_right", Action::PanRight),
+];
+
+// Buttons that are used when the config doesn't bind them to something else
+const DEFAULT_BINDINGS: [(Button, Action); 42] = [
+    (Button::Mouse(MouseButton::Left), Action::Paint),
+    (Button::Keyboard(Key::Tab), Action::NextTool),
+    (Button::Keyboard(Key::K), Action::NextBrushShape),
+    (Button::Keyboard(Key::P), Action::ToggleSpray),
+    (Button::Keyboard(Key::LeftBracket), Action::SprayThinner),
+    (Button::Keyboard(Key::RightBracket), Action::SprayThicker),
+    (Button::Keyboard(Key::O), Action::ToggleReplace),
+    (Button::Mouse(MouseButton::Right), Action::PaintForce),
+    (Button::Mouse(MouseButton::Middle), Action::DragCamera),
+    (Button::Keyboard(Key::Equals), Action::BrushBigger),
+    (Button::Keyboard(Key::Minus), Action::BrushSmaller),
+    (Button::Keyboard(Key::Space), Action::Pause),
+    (Button::Keyboard(Key::Period), Action::Step),
+    (Button::Keyboard(Key::U), Action::Undo),
+    (Button::Keyboard(Key::H), Action::ToggleHelp),
+    (Button::Keyboard(Key::I), Action::ToggleInspector),
+    (Button::Keyboard(Key::F1), Action::ToggleStats),
+    (Button::Keyboard(Key::F2), Action::ToggleProcessedOverlay),
+    (Button::Keyboard(Key::F3), Action::ToggleChangesOverlay),
+    (Button::Keyboard(Key::F4), Action::CycleHeatMap),
+    (Button::Keyboard(Key::D1), Action::SelectElement(SAND_CELL)),
+    (Button::Keyboard(Key::D2), Action::SelectElement(STEEL_CELL)),
+    (Button::Keyboard(Key::D3), Action::SelectElement(WATER_CELL)),
+    (Button::Keyboard(Key::D4), Action::SelectElement(FIRE_CELL)),
+    (Button::Keyboard(Key::D5), Action::SelectElement(EMPTY_CELL)),
+    (
+        Button::Keyboard(Key::D6),
+        Action::SelectElement(GUNPOWDER_CELL),
+    ),
+    (
+        Button::Keyboard(Key::D7),
+        Action::SelectElement(SOURCE_CELL),
+    ),
+    (Button::Keyboard(Key::D8), Action::SelectElement(VOID_CELL)),
+    (Button::Keyboard(Key::D9), Action::SelectElement(CLONE_CELL)),
+    (Button::Keyboard(Key::R), Action::ToggleRigidBodies),
+    (Button::Keyboard(Key::C), Action::ClearForces),
+    (Button::Keyboard(Key::G), Action::FlipGravity),
+    (Button::Keyboard(Key::T), Action::TurnGravity),
+    (Button::Keyboard(Key::Z), Action::ToggleGravity),
+    (Button::Keyboard(Key::B), Action::CycleSideEdges),
+    (Button::Keyboard(Key::V), Action::CycleTopBottomEdges),
+    (Button::Keyboard(Key::F5), Action::SaveWorld),
+    (Button::Keyboard(Key::F9), Action::LoadWorld),
+    (Button::Keyboard(Key::W), Action::PanUp),
+    (Button::Keyboard(Key::S), Action::PanDown),
+    (Button::Keyboard(Key::A), Action::PanLeft),
+    (Button::Keyboard(Key::D), Action::PanRight),
+];
+
+// Names mouse buttons can have in the config file
+const MOUSE_NAMES: [(&str, MouseButton); 3] = [
+    ("MouseLeft", MouseButton::Left),
+    ("MouseRight", MouseButton::Right),
+    ("MouseMiddle", MouseButton::Middle),
+];
+
+// Names keys can have in the config file
+const KEY_NAMES: [(&str, Key); 67] = [
+    ("A", Key::A),
+    ("B", Key::B),
+    ("C", Key::C),
+    ("D", Key::D),
+    ("E", Key::E),
+    ("F", Key::F),
+    ("G", Key::G),
+    ("H", Key::H),
+    ("I", Key::I),
+    ("J", Key::J),
+    ("K", Key::K),
+    ("L", Key::L),
+    ("M", Key::M),
+    ("N", Key::N),
+    ("O", Key::O),
+    ("P", Key::P),
+    ("Q", Key::Q),
+    ("R", Key::R),
+    ("S", Key::S),
+    ("T", Key::T),
+    ("U", Key::U),
+    ("V", Key::V),
+    ("W", Key::W),
+    ("X", Key::X),
+    ("Y", Key::Y),
+    ("Z", Key::Z),
+    ("0", Key::D0),
+    ("1", Key::D1),
+    ("2", Key::D2),
+    ("3", Key::D3),
+    ("4", Key::D4),
+    ("5", Key::D5),
+    ("6", Key::D6),
+    ("7", Key::D7),
+    ("8", Key::D8),
+    ("9", Key::D9),
+    ("F1", Key::F1),
+    ("F2", Key::F2),
+    ("F3", Key::F3),
+    ("F4", Key::F4),
+    ("F5", Key::F5),
+    ("F6", Key::F6),
+    ("F7", Key::F7),
+    ("F8", Key::F8),
+    ("F9", Key::F9),
+    ("F10", Key::F10),
+    ("F11", Key::F11),
+    ("F12", Key::F12),
+    ("Space", Key::Space),
+    ("Tab", Key::Tab),
+    ("Enter", Key::Return),
+    ("Backspace", Key::Backspace),
+    ("Delete", Key::Delete),
+    ("Insert", Key::Insert),
+    ("Home", Key::Home),
+    ("End", Key::End),
+    ("PageUp", Key::PageUp),
+    ("PageDown", Key::PageDown),
+    ("Up", Key::Up),
+    ("Down", Key::Down),
+    ("Left", Key::Left),
+    ("Right", Key::Right),
+    ("Minus", Key::Minus),
+    ("Equals", Key::Equals),
+    ("Period", Key::Period),
+    ("LeftBracket", Key::LeftBracket),
+    ("RightBracket", Key::RightBracket),
+];
+
+// Which action every key and mouse button does
+pub struct Bindings {
+    buttons: HashMap<Button, Action>,
+    warnings: Vec<String>,
+}
+
+impl Bindings {
+    // Default buttons with the bindings from the config on top, the config maps
+    // action names to key or mouse button names like save_world = "F5"
+    //
+    // Binding two actions to the same button is an error. Default buttons the config
+    // takes away from an action it doesn't rebind end up in warnings
+    pub fn from_config(config: &HashMap<String, String>) -> Result<Self, String> {
+        let mut buttons: HashMap<Button, Action> = DEFAULT_BINDINGS.into_iter().collect();
+        let mut bound_by_config: HashMap<Button, &str> = HashMap::new();
+
+        // Sorted so the errors are the same every time
+        let mut entries: Vec<(&String, &String)> = config.iter().collect();
+        entries.sort();
+
+        for (action_name, button_name) in entries {
+            let action = action_from_name(action_name)
+                .ok_or_else(|| format!("unknown action \"{}\" in keys", action_name))?;
+            let button = button_from_name(button_name).ok_or_else(|| {
+                format!(
+                    "unknown key \"{}\" for {} in keys",
+                    button_name, action_name
+                )
+            })?;
+            if let Some(other) = bound_by_config.insert(button, action_name) {
+                return Err(format!(
+                    "{} and {} are both bound to \"{}\" in keys",
+                    other, action_name, button_name
+                ));
+            }
+
+            // An action only has one button, so the default button stops doing it
+            buttons.retain(|_, bound| *bound != action);
+            buttons.insert(button, action);
+        }
+
+        let mut warnings = Vec::new();
+        for (button, action) in DEFAULT_BINDINGS {
+            let rebound = config.contains_key(&action_name(action));
+            match buttons.get(&button) {
+                Some(&taken_by) if taken_by != action && !rebound => warnings.push(format!(
+                    "{} takes {} from {}, which has no key now",
+                    action_name(taken_by),
+                    button_name(button),
+                    action_name(action)
+                )),
+                _ => {}
+            }
+        }
+
+        Ok(Bindings { buttons, warnings })
+    }
+
+    // Action the button is bound to
+    pub fn action(&self, button: Button) -> Option<Action> {
+        self.buttons.get(&button).copied()
+    }
+
+    // Problems with the config that aren't bad enough to stop the sim
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    // Every bound action with the name of its button, for the help
+    pub fn help(&self) -> Vec<(String, String)> {
+        actions()
+            .into_iter()
+            .filter_map(|(action_name, action)| {
+                let (&button, _) = self.buttons.iter().find(|(_, bound)| **bound == action)?;
+                Some((button_name(button), action_name))
+            })
+            .collect()
+    }
+}
+
+// Every action with its name, selecting a element comes after the rest and there is a
+// select action for every element, like select_sand or select_salt_water
+fn actions() -> Vec<(String, Action)> {
+    let select_actions = (0..ELEMENT_COUNT).map(|cell_type| {
+        let name = element_name(cell_type).to_lowercase().replace(' ', "_");
+        (format!("select_{}", name), Action::SelectElement(cell_type))
+    });
+    ACTIONS
+        .iter()
+        .map(|&(name, action)| (name.to_string(), action))
+        .chain(select_actions)
+        .collect()
+}
+
+fn action_from_name(name: &str) -> Option<Action> {
+    actions()
+        .into_iter()
+        .find(|(action_name, _)| action_name == name)
+        .map(|(_, action)| action)
+}
+
+fn action_name(action: Action) -> String {
+    actions()
+        .into_iter()
+        .find(|&(_, named)| named == action)
+        .map(|(name, _)| name)
+        .unwrap_or_else(|| format!("{:?}", action))
+}
+
+fn button_from_name(name: &str) -> Option<Button> {
+    let key = KEY_NAMES
+        .iter()
+        .find(|(key_name, _)| key_name.eq_ignore_ascii_case(name))
+        .map(|&(_, key)| Button::Keyboard(key));
+    let mouse = || {
+        MOUSE_NAMES
+            .iter()
+            .find(|(mouse_name, _)| mouse_name.eq_ignore_ascii_case(name))
+            .map(|&(_, mouse_button)| Button::Mouse(mouse_button))
+    };
+    key.or_else(mouse)
+}
+
+fn button_name(button: Button) -> String {
+    let name = match button {
+        Button::Keyboard(key) => KEY_NAMES
+            .iter()
+            .find(|(_, named)| *named == key)
+            .map(|&(name, _)| name),
+        Button::Mouse(mouse_button) => MOUSE_NAMES
+            .iter()
+            .find(|(_, named)| *named == mouse_button)
+            .map(|&(name, _)| name),
+        _ => None,
+    };
+    name.map(str::to_string)
+        .unwrap_or_else(|| format!("{:?}", button))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|&(action, button)| (action.to_string(), button.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn two_actions_on_one_key_is_an_error() {
+        let config = keys(&[("save_world", "F6"), ("load_world", "f6")]);
+
+        match Bindings::from_config(&config) {
+            Ok(_) => panic!("both actions got F6"),
+            Err(error) => assert_eq!(
+                error,
+                "load_world and save_world are both bound to \"F6\" in keys"
+            ),
+        }
+    }
+
+    #[test]
+    fn taking_a_default_key_gives_a_warning() {
+        let bindings = Bindings::from_config(&keys(&[("save_world", "W")])).unwrap();
+
+        assert_eq!(
+            bindings.action(Button::Keyboard(Key::W)),
+            Some(Action::SaveWorld)
+        );
+        assert_eq!(bindings.action(Button::Keyboard(Key::F5)), None);
+        assert_eq!(
+            bindings.warnings(),
+            ["save_world takes W from pan_up, which has no key now"]
+        );
+
+        // Moving pan_up somewhere else as well is on purpose
+        let config = keys(&[("save_world", "W"), ("pan_up", "Up")]);
+        assert!(
+            Bindings::from_config(&config)
+                .unwrap()
+                .warnings()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn every_element_can_be_selected() {
+        for cell_type in 0..ELEMENT_COUNT {
+            let name = element_name(cell_type).to_lowercase().replace(' ', "_");
+            let config = keys(&[(&format!("select_{}", name), "F12")]);
+            let bindings = Bindings::from_config(&config).unwrap();
+
+            assert_eq!(
+                bindings.action(Button::Keyboard(Key::F12)),
+                Some(Action::SelectElement(cell_type))
+            );
+        }
+        assert!(Bindings::from_config(&keys(&[("select_salt_water", "Y")])).is_ok());
+        assert!(Bindings::from_config(&keys(&[("select_unobtainium", "Y")])).is_err());
+    }
+}
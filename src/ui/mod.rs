@@ -1,2 +1,6 @@
 pub mod button;
+pub mod camera;
+pub mod graph;
+pub mod input;
 pub mod text;
+pub mod tooltip;
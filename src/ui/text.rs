@@ -4,28 +4,36 @@ pub struct Label {
     x: f64,
     y: f64,
     label_text: String,
+    font_size: u32,
 }
 
 impl Label {
     pub fn new(x: f64, y: f64, label_text: String) -> Self {
         Self {
-            x: x,
-            y: y,
-            label_text: label_text,
+            x,
+            y,
+            label_text,
+            font_size: 24,
         }
     }
 
+    // Changes the font size of the label, the default is 24
+    pub fn with_font_size(mut self, font_size: u32) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
     pub fn draw<G, C>(&self, context: &Context, graphics: &mut G, glyphs: &mut C)
     where
         G: Graphics<Texture = C::Texture>,
         C: character::CharacterCache,
     {
-        text::Text::new_color([1.0, 1.0, 1.0, 1.0], 24)
+        text::Text::new_color([1.0, 1.0, 1.0, 1.0], self.font_size)
             .draw(
                 &self.label_text,
                 glyphs,
                 &context.draw_state,
-                context.transform.trans(self.x as f64, self.y as f64),
+                context.transform.trans(self.x, self.y),
                 graphics,
             )
             .unwrap();
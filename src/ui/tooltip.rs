@@ -0,0 +1,79 @@
+use piston_window::graphics::{Context, Graphics, character, rectangle};
+
+use super::text::Label;
+
+// Space between the text and the edge of the tooltip
+const PADDING: f64 = 6.0;
+
+// Box of text that floats next to the mouse
+pub struct Tooltip {
+    x: f64,
+    y: f64,
+    lines: Vec<String>,
+    font_size: u32,
+    background: [f32; 4],
+}
+
+impl Tooltip {
+    pub fn new(x: f64, y: f64, lines: Vec<String>, background: [f32; 4]) -> Self {
+        Tooltip {
+            x,
+            y,
+            lines,
+            font_size: 13,
+            background,
+        }
+    }
+
+    fn line_height(&self) -> f64 {
+        self.font_size as f64 + 5.0
+    }
+
+    // Draws the tooltip with its top left corner at the giving cords, it gets moved to
+    // the other side of them if it would go past the right or bottom of the area
+    pub fn draw<G, C>(
+        &self,
+        (area_width, area_height): (f64, f64),
+        context: &Context,
+        graphics: &mut G,
+        glyphs: &mut C,
+    ) where
+        G: Graphics<Texture = C::Texture>,
+        C: character::CharacterCache,
+    {
+        let text_width = self
+            .lines
+            .iter()
+            .map(|line| glyphs.width(self.font_size, line).unwrap_or(0.0))
+            .fold(0.0, f64::max);
+        let width = text_width + PADDING * 2.0;
+        let height = self.lines.len() as f64 * self.line_height() + PADDING * 2.0;
+
+        let x = if self.x + width > area_width {
+            (self.x - width).max(0.0)
+        } else {
+            self.x
+        };
+        let y = if self.y + height > area_height {
+            (self.y - height).max(0.0)
+        } else {
+            self.y
+        };
+
+        rectangle(
+            self.background,
+            [x, y, width, height],
+            context.transform,
+            graphics,
+        );
+        for (index, line) in self.lines.iter().enumerate() {
+            let label = Label::new(
+                x + PADDING,
+                y + PADDING + (index + 1) as f64 * self.line_height() - 5.0,
+                line.clone(),
+            )
+            .with_font_size(self.font_size);
+            label.draw(context, graphics, glyphs);
+        }
+    }
+}
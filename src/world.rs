@@ -0,0 +1,197 @@
+use crate::cell::*;
+use crate::grid::{ASCII_HEADER, Boundaries, Grid, MAX_GRAVITY_STRENGTH, MAX_WORLD_SIZE};
+use std::fs;
+use std::io::{self, Error, ErrorKind};
+use std::path::Path;
+
+// First line of every world file
+const WORLD_HEADER: &str = "particle-sim world 1";
+
+// Saves the grid and its settings to a text file
+//
+// The file starts with the settings, one per line, and then has one line per row of cells.
+// Every cell is its element type, followed by its life time and spawn type when it has them
+// like "10,0,3". Painted force fields are listed after the cells
+pub fn save_world(grid: &Grid, path: &Path) -> io::Result<()> {
+    let mut text = String::new();
+
+    text.push_str(&format!("{}\n", WORLD_HEADER));
+    text.push_str(&format!("size {} {}\n", grid.width, grid.height));
+    text.push_str(&format!("wind {} {}\n", grid.wind.0, grid.wind.1));
+    text.push_str(&format!("rigid_bodies {}\n", grid.rigid_bodies));
+    text.push_str(&format!("gravity {} {}\n", grid.gravity.0, grid.gravity.1));
+    text.push_str(&format!("gravity_strength {}\n", grid.gravity_strength));
+    let boundaries = grid.boundaries;
+    text.push_str(&format!(
+        "boundaries {} {} {} {}\n",
+        boundaries.left, boundaries.right, boundaries.top, boundaries.bottom
+    ));
+
+    text.push_str("cells\n");
+    for row in &grid.grid {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|cell| {
+                if cell.life_time == 0 && cell.spawn_type == EMPTY_CELL {
+                    cell.cell_type.to_string()
+                } else {
+                    format!("{},{},{}", cell.cell_type, cell.life_time, cell.spawn_type)
+                }
+            })
+            .collect();
+        text.push_str(&cells.join(" "));
+        text.push('\n');
+    }
+
+    text.push_str("forces\n");
+    for (y, row) in grid.force_field.iter().enumerate() {
+        for (x, &(force_x, force_y)) in row.iter().enumerate() {
+            if force_x != 0.0 || force_y != 0.0 {
+                text.push_str(&format!("{} {} {} {}\n", x, y, force_x, force_y));
+            }
+        }
+    }
+
+    fs::write(path, text)
+}
+
+// Loads a grid that was saved with save_world, or a ASCII world like Grid::to_ascii writes
+pub fn load_world(path: &Path) -> io::Result<Grid> {
+    let text = fs::read_to_string(path)?;
+    if text.trim_start().starts_with(ASCII_HEADER) {
+        return Grid::from_ascii(&text).map_err(|error| invalid_data(&error));
+    }
+    let mut lines = text.lines();
+
+    if lines.next() != Some(WORLD_HEADER) {
+        return Err(invalid_data("not a world file"));
+    }
+
+    let mut size: Option<(i64, i64)> = None;
+    let mut wind = (0.0, 0.0);
+    let mut rigid_bodies = false;
+    let mut gravity = (0, 1);
+    let mut gravity_strength = 1.0;
+    let mut boundaries = Boundaries::default();
+
+    // Settings
+    for line in lines.by_ref() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        match parts.as_slice() {
+            ["size", width, height] => size = Some((parse(width)?, parse(height)?)),
+            ["wind", wind_x, wind_y] => wind = (parse(wind_x)?, parse(wind_y)?),
+            ["rigid_bodies", value] => rigid_bodies = parse(value)?,
+            ["gravity", gravity_x, gravity_y] => {
+                gravity = (parse(gravity_x)?, parse(gravity_y)?);
+                if !(-1..=1).contains(&gravity.0) || !(-1..=1).contains(&gravity.1) {
+                    return Err(invalid_data(
+                        "gravity has to be one of the 8 directions or 0 0",
+                    ));
+                }
+            }
+            ["gravity_strength", strength] => {
+                gravity_strength = parse(strength)?;
+                if !(0.0..=MAX_GRAVITY_STRENGTH).contains(&gravity_strength) {
+                    return Err(invalid_data(&format!(
+                        "gravity_strength has to be between 0 and {}",
+                        MAX_GRAVITY_STRENGTH
+                    )));
+                }
+            }
+            ["boundaries", left, right, top, bottom] => {
+                boundaries = Boundaries {
+                    left: parse(left)?,
+                    right: parse(right)?,
+                    top: parse(top)?,
+                    bottom: parse(bottom)?,
+                }
+            }
+            ["cells"] => break,
+            _ => return Err(invalid_data(&format!("unknown setting \"{}\"", line))),
+        }
+    }
+
+    let (width, height) = size.ok_or_else(|| invalid_data("missing size"))?;
+    if width <= 0 || height <= 0 {
+        return Err(invalid_data("size has to be bigger than 0"));
+    }
+    if width > MAX_WORLD_SIZE || height > MAX_WORLD_SIZE {
+        return Err(invalid_data(&format!(
+            "size can be at most {}x{}, it is {}x{}",
+            MAX_WORLD_SIZE, MAX_WORLD_SIZE, width, height
+        )));
+    }
+
+    let mut grid = Grid::new(width, height);
+    grid.wind = wind;
+    grid.rigid_bodies = rigid_bodies;
+    grid.gravity = gravity;
+    grid.gravity_strength = gravity_strength;
+    grid.boundaries = boundaries;
+
+    // Cells
+    for y in 0..height as usize {
+        let line = lines
+            .next()
+            .ok_or_else(|| invalid_data(&format!("missing row {}", y)))?;
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.len() != width as usize {
+            return Err(invalid_data(&format!("row {} has the wrong width", y)));
+        }
+
+        for (x, token) in tokens.into_iter().enumerate() {
+            let values: Vec<&str> = token.split(',').collect();
+            let cell = match values.as_slice() {
+                [cell_type] => Cell::from_type(parse_element(cell_type)?),
+                [cell_type, life_time, spawn_type] => {
+                    let mut cell = Cell::from_type(parse_element(cell_type)?);
+                    cell.life_time = parse(life_time)?;
+                    cell.spawn_type = parse_element(spawn_type)?;
+                    cell
+                }
+                _ => return Err(invalid_data(&format!("bad cell \"{}\"", token))),
+            };
+            grid.grid[y][x] = cell;
+        }
+    }
+
+    // Force fields
+    if lines.next() != Some("forces") {
+        return Err(invalid_data("missing forces"));
+    }
+    for line in lines {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let [x, y, force_x, force_y] = parts.as_slice() else {
+            return Err(invalid_data(&format!("bad force \"{}\"", line)));
+        };
+        let (x, y): (usize, usize) = (parse(x)?, parse(y)?);
+        if x >= width as usize || y >= height as usize {
+            return Err(invalid_data(&format!(
+                "force at {} {} is outside the world",
+                x, y
+            )));
+        }
+        grid.force_field[y][x] = (parse(force_x)?, parse(force_y)?);
+    }
+
+    Ok(grid)
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> io::Result<T> {
+    value
+        .parse()
+        .map_err(|_| invalid_data(&format!("bad value \"{}\"", value)))
+}
+
+// Parses a element type, only the elements that exist are allowed
+fn parse_element(value: &str) -> io::Result<u8> {
+    let cell_type: u8 = parse(value)?;
+    if cell_type >= ELEMENT_COUNT {
+        return Err(invalid_data(&format!("unknown element {}", cell_type)));
+    }
+    Ok(cell_type)
+}
+
+fn invalid_data(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
@@ -0,0 +1,104 @@
+use particle_sim::cell::*;
+use particle_sim::grid::Grid;
+use particle_sim::world::load_world;
+use std::fs;
+
+mod common;
+use common::temp_path;
+
+#[test]
+fn every_element_round_trips() {
+    let mut grid = Grid::new(ELEMENT_COUNT as i64, 2);
+    grid.seed(9);
+    for cell_type in 0..ELEMENT_COUNT {
+        grid.grid[0][cell_type as usize] = Cell::from_type(cell_type);
+        grid.grid[1][(ELEMENT_COUNT - 1 - cell_type) as usize] = Cell::from_type(cell_type);
+    }
+
+    let text = grid.to_ascii();
+    let loaded = Grid::from_ascii(&text).unwrap();
+
+    assert_eq!((loaded.width, loaded.height), (grid.width, grid.height));
+    assert_eq!(loaded.rng_seed(), Some(9));
+    for (row, loaded_row) in grid.grid.iter().zip(&loaded.grid) {
+        for (cell, loaded_cell) in row.iter().zip(loaded_row) {
+            assert_eq!(cell.cell_type, loaded_cell.cell_type);
+        }
+    }
+    assert_eq!(loaded.to_ascii(), text);
+}
+
+#[test]
+fn scenes_can_be_written_inline() {
+    let grid = Grid::from_ascii(
+        "
+        ..S..
+        .WWW.
+        #####
+        ",
+    )
+    .unwrap();
+
+    assert_eq!((grid.width, grid.height), (5, 3));
+    assert_eq!(grid.rng_seed(), None);
+    assert_eq!(grid.grid[0][2].cell_type, SAND_CELL);
+    assert_eq!(grid.grid[1][1].cell_type, WATER_CELL);
+    assert_eq!(grid.grid[2][4].cell_type, STEEL_CELL);
+    assert!(grid.to_ascii().ends_with("cells\n..S..\n.WWW.\n#####\n"));
+}
+
+#[test]
+fn the_same_seed_runs_the_same_way() {
+    let scene = "
+        particle-sim ascii 1
+        size 6 4
+        seed 5
+        cells
+        .SSWW.
+        .SSWW.
+        ......
+        ######
+    ";
+    let run = || {
+        let mut grid = Grid::from_ascii(scene).unwrap();
+        for _ in 0..10 {
+            grid.update();
+        }
+        grid.to_ascii()
+    };
+
+    assert_eq!(run(), run());
+}
+
+#[test]
+fn bad_ascii_is_refused() {
+    let error = |text: &str| match Grid::from_ascii(text) {
+        Ok(_) => panic!("{:?} was read", text),
+        Err(error) => error,
+    };
+
+    assert_eq!(error(""), "there are no cells");
+    assert_eq!(error("..\n..."), "row 1 has the wrong width");
+    assert_eq!(error(".?"), "unknown element '?' at 1, 0");
+    assert_eq!(
+        error("particle-sim ascii 1\nsize 3 1\ncells\n.."),
+        "size says 3x1 but the cells are 2x1"
+    );
+    assert_eq!(
+        error("particle-sim ascii 1\nwind 1 0\ncells\n.."),
+        "unknown setting \"wind 1 0\""
+    );
+}
+
+#[test]
+fn ascii_worlds_can_be_loaded() {
+    let path = temp_path("ascii-world");
+    fs::write(&path, "particle-sim ascii 1\nsize 3 2\ncells\nS.W\n###\n").unwrap();
+    let grid = load_world(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!((grid.width, grid.height), (3, 2));
+    assert_eq!(grid.grid[0][0].cell_type, SAND_CELL);
+    assert_eq!(grid.grid[0][2].cell_type, WATER_CELL);
+    assert_eq!(grid.grid[1][1].cell_type, STEEL_CELL);
+}
@@ -0,0 +1,75 @@
+use particle_sim::cell::*;
+use particle_sim::grid::{Boundary, Grid};
+
+mod common;
+use common::count;
+
+#[test]
+fn sand_falling_through_a_wrapping_floor_comes_back_at_the_top() {
+    let mut grid = Grid::new(5, 10);
+    grid.boundaries.top = Boundary::Wrap;
+    grid.boundaries.bottom = Boundary::Wrap;
+    grid.grid[9][2] = Cell::new_sand();
+
+    grid.update();
+
+    assert_eq!(grid.grid[0][2].cell_type, SAND_CELL);
+    assert_eq!(count(&grid, SAND_CELL), 1);
+}
+
+#[test]
+fn void_floor_deletes_what_falls_through_it() {
+    let mut grid = Grid::new(6, 8);
+    grid.boundaries.bottom = Boundary::Void;
+    for x in 0..6 {
+        grid.grid[2][x] = Cell::new_sand();
+        grid.grid[4][x] = Cell::new_oil();
+    }
+
+    for _ in 0..40 {
+        grid.update();
+    }
+
+    assert_eq!(count(&grid, EMPTY_CELL), 6 * 8);
+}
+
+#[test]
+fn walls_keep_everything_in() {
+    let mut grid = Grid::new(6, 8);
+    for x in 0..6 {
+        grid.grid[2][x] = Cell::new_sand();
+        grid.grid[4][x] = Cell::new_oil();
+    }
+
+    for _ in 0..40 {
+        grid.update();
+    }
+
+    assert_eq!(count(&grid, SAND_CELL), 6);
+    assert_eq!(count(&grid, OIL_CELL), 6);
+}
+
+#[test]
+fn water_spreads_across_a_wrapping_side() {
+    let mut grid = Grid::new(8, 3);
+    grid.boundaries.left = Boundary::Wrap;
+    grid.boundaries.right = Boundary::Wrap;
+    // Steel wall in the middle so the only way to the right half is around the edge
+    for y in 0..3 {
+        grid.grid[y][4] = Cell::new_steel();
+    }
+    for x in 0..4 {
+        grid.grid[0][x] = Cell::new_water();
+        grid.grid[1][x] = Cell::new_water();
+    }
+
+    for _ in 0..200 {
+        grid.update();
+    }
+
+    let right_half = (5..8)
+        .filter(|&x| (0..3).any(|y| grid.grid[y][x].cell_type == WATER_CELL))
+        .count();
+    assert!(right_half > 0);
+    assert_eq!(count(&grid, WATER_CELL), 8);
+}
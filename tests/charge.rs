@@ -0,0 +1,91 @@
+use particle_sim::cell::*;
+use particle_sim::grid::{CHARGE_HEAD, Grid};
+
+mod common;
+use common::count;
+
+// A world without gravity with a straight wire along row 10 from x 1 to 18
+fn wire_grid() -> Grid {
+    let mut grid = Grid::new(20, 20);
+    grid.seed(9);
+    grid.gravity = (0, 0);
+    for x in 1..19 {
+        grid.grid[10][x] = Cell::new_wire();
+    }
+    grid
+}
+
+fn wire_charge(grid: &Grid) -> Vec<u8> {
+    (1..19).map(|x| grid.charge[10][x]).collect()
+}
+
+#[test]
+fn pulse_travels_along_a_wire() {
+    let mut grid = wire_grid();
+    grid.charge[10][1] = CHARGE_HEAD;
+
+    for tick in 1..18 {
+        grid.update();
+
+        // The head moved one cell and the cells behind it count down
+        let mut expected = vec![0; 18];
+        for (behind, slot) in expected[..=tick].iter_mut().rev().enumerate() {
+            *slot = CHARGE_HEAD.saturating_sub(behind as u8);
+        }
+        assert_eq!(wire_charge(&grid), expected, "tick {tick}");
+    }
+}
+
+#[test]
+fn pulse_doesnt_flow_back() {
+    let mut grid = wire_grid();
+    // Start in the middle so the pulse goes both ways
+    grid.charge[10][9] = CHARGE_HEAD;
+
+    let mut heads_seen = 0;
+    for _ in 0..40 {
+        grid.update();
+        heads_seen += wire_charge(&grid)
+            .iter()
+            .filter(|&&charge| charge == CHARGE_HEAD)
+            .count();
+    }
+
+    // Every cell but the start was a head once, after that the wire is quiet
+    assert_eq!(heads_seen, 17);
+    assert!(wire_charge(&grid).iter().all(|&charge| charge == 0));
+}
+
+#[test]
+fn sparks_set_flammables_on_fire() {
+    let mut grid = wire_grid();
+    for x in 1..19 {
+        grid.grid[9][x] = Cell::new_oil();
+    }
+    grid.charge[10][1] = CHARGE_HEAD;
+
+    grid.update();
+
+    // The head is at x 2 now and sparks everything around it
+    for x in 1..4 {
+        assert_eq!(grid.grid[9][x].cell_type, FIRE_CELL, "oil at {x}");
+    }
+}
+
+#[test]
+fn sparks_split_water() {
+    let mut grid = wire_grid();
+    for x in 1..19 {
+        grid.grid[9][x] = Cell::new_water();
+    }
+    grid.charge[10][1] = CHARGE_HEAD;
+
+    grid.update();
+
+    // The 3 water cells around the head turned into gas, the rest is still water
+    for x in 1..4 {
+        assert_ne!(grid.grid[9][x].cell_type, WATER_CELL, "water at {x}");
+    }
+    assert_eq!(count(&grid, WATER_CELL), 15);
+    assert_eq!(count(&grid, HYDROGEN_CELL) + count(&grid, OXYGEN_CELL), 3);
+}
@@ -0,0 +1,33 @@
+// Helpers the integration tests share, every test file only uses some of them
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+use particle_sim::grid::Grid;
+
+// How many cells of the giving element are in the grid
+pub fn count(grid: &Grid, cell_type: u8) -> usize {
+    grid.grid
+        .iter()
+        .flatten()
+        .filter(|cell| cell.cell_type == cell_type)
+        .count()
+}
+
+// Positions of every cell of the giving element, row by row
+pub fn cells_of(grid: &Grid, cell_type: u8) -> Vec<(usize, usize)> {
+    let mut cells = Vec::new();
+    for y in 0..grid.height as usize {
+        for x in 0..grid.width as usize {
+            if grid.grid[y][x].cell_type == cell_type {
+                cells.push((x, y));
+            }
+        }
+    }
+    cells
+}
+
+// Path in the temp dir that no other test, or other run of the tests, uses at the same time
+pub fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("particle-sim-{}-{}.txt", std::process::id(), name))
+}
@@ -0,0 +1,308 @@
+use particle_sim::cell::*;
+use particle_sim::grid::{Boundaries, Boundary, Grid, rotate_direction};
+
+const SIZE: i64 = 24;
+const TICKS: usize = 80;
+
+// Something to run and what it is allowed to change
+struct Scenario {
+    name: String,
+    grid: Grid,
+    // Elements made or used up by a reaction, their count can change
+    reacting: Vec<u8>,
+    // Elements that turn into each other, the sum of their counts stays the same
+    groups: Vec<Vec<u8>>,
+}
+
+impl Scenario {
+    fn new(name: impl Into<String>, grid: Grid) -> Self {
+        Scenario {
+            name: name.into(),
+            grid,
+            reacting: Vec::new(),
+            groups: Vec::new(),
+        }
+    }
+
+    fn reacting(mut self, elements: &[u8]) -> Self {
+        self.reacting.extend_from_slice(elements);
+        self
+    }
+
+    fn group(mut self, elements: &[u8]) -> Self {
+        self.groups.push(elements.to_vec());
+        self
+    }
+
+    // Steps the grid and checks the invariants after every tick
+    fn run(mut self) {
+        let start = counts(&self.grid);
+        for tick in 1..=TICKS {
+            self.grid.update();
+            let now = counts(&self.grid);
+            let name = format!("{} at tick {}", self.name, tick);
+
+            // Nothing leaves the grid and every cell holds one particle
+            assert_eq!(self.grid.grid.len(), SIZE as usize, "{}", name);
+            assert!(
+                self.grid.grid.iter().all(|row| row.len() == SIZE as usize),
+                "{}",
+                name
+            );
+            assert!(
+                self.grid
+                    .grid
+                    .iter()
+                    .flatten()
+                    .all(|cell| cell.cell_type < ELEMENT_COUNT),
+                "{}: unknown element",
+                name
+            );
+
+            for group in &self.groups {
+                let sum = |counts: &[u32]| group.iter().map(|&e| counts[e as usize]).sum::<u32>();
+                assert_eq!(sum(&now), sum(&start), "{}: group {:?}", name, group);
+            }
+            for cell_type in 1..ELEMENT_COUNT {
+                let free = self.reacting.contains(&cell_type)
+                    || self.groups.iter().any(|group| group.contains(&cell_type));
+                if !free {
+                    assert_eq!(
+                        now[cell_type as usize],
+                        start[cell_type as usize],
+                        "{}: {} appeared or disappeared",
+                        name,
+                        element_name(cell_type)
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn counts(grid: &Grid) -> Vec<u32> {
+    let mut counts = vec![0; ELEMENT_COUNT as usize];
+    for cell in grid.grid.iter().flatten() {
+        counts[cell.cell_type as usize] += 1;
+    }
+    counts
+}
+
+fn fill(grid: &mut Grid, (left, top): (i64, i64), (width, height): (i64, i64), cell_type: u8) {
+    for y in top..top + height {
+        for x in left..left + width {
+            grid.grid[y as usize][x as usize] = Cell::from_type(cell_type);
+        }
+    }
+}
+
+fn new_grid(seed: u64) -> Grid {
+    let mut grid = Grid::new(SIZE, SIZE);
+    grid.seed(seed);
+    grid
+}
+
+// Every way gravity can point, and no gravity at all
+fn gravities() -> Vec<(i64, i64)> {
+    let mut gravities: Vec<(i64, i64)> =
+        (0..8).map(|step| rotate_direction((0, 1), step)).collect();
+    gravities.push((0, 0));
+    gravities
+}
+
+// Elements that change on their own, and what they are allowed to change
+fn alone_rules(cell_type: u8) -> (&'static [u8], &'static [u8]) {
+    match cell_type {
+        // Fire burns out and gives off smoke
+        FIRE_CELL => (&[FIRE_CELL, SMOKE_CELL], &[]),
+        // Smoke and steam fade away after their life time
+        SMOKE_CELL | STEAM_CELL => (&[SMOKE_CELL, STEAM_CELL], &[]),
+        // Sources keep making water
+        SOURCE_CELL => (&[WATER_CELL], &[]),
+        _ => (&[], &[]),
+    }
+}
+
+#[test]
+fn every_element_alone_is_conserved() {
+    for cell_type in 1..ELEMENT_COUNT {
+        for (index, gravity) in gravities().into_iter().enumerate() {
+            for mode in [Boundary::Wall, Boundary::Wrap] {
+                let mut grid = new_grid(index as u64);
+                grid.gravity = gravity;
+                grid.boundaries = Boundaries {
+                    left: mode,
+                    right: mode,
+                    top: mode,
+                    bottom: mode,
+                };
+                fill(&mut grid, (8, 4), (7, 5), cell_type);
+                fill(&mut grid, (1, 16), (3, 3), cell_type);
+
+                let (reacting, _) = alone_rules(cell_type);
+                Scenario::new(
+                    format!(
+                        "{} with gravity {:?} and {} edges",
+                        element_name(cell_type),
+                        gravity,
+                        mode
+                    ),
+                    grid,
+                )
+                .reacting(reacting)
+                .run();
+            }
+        }
+    }
+}
+
+#[test]
+fn elements_that_do_not_react_are_conserved_together() {
+    let movers = [
+        SAND_CELL,
+        HONEY_CELL,
+        OIL_CELL,
+        CO2_CELL,
+        HYDROGEN_CELL,
+        OXYGEN_CELL,
+        GUNPOWDER_CELL,
+        SALT_CELL,
+        WET_SAND_CELL,
+    ];
+
+    for (index, gravity) in gravities().into_iter().enumerate() {
+        for rigid_bodies in [false, true] {
+            let mut grid = new_grid(index as u64);
+            grid.gravity = gravity;
+            grid.rigid_bodies = rigid_bodies;
+            for (slot, &cell_type) in movers.iter().enumerate() {
+                let slot = slot as i64;
+                fill(
+                    &mut grid,
+                    (2 + slot % 3 * 7, 2 + slot / 3 * 7),
+                    (5, 4),
+                    cell_type,
+                );
+            }
+            // Structures in the middle of it all
+            fill(&mut grid, (6, 12), (12, 1), STEEL_CELL);
+            fill(&mut grid, (4, 20), (3, 1), GLASS_CELL);
+            fill(&mut grid, (15, 20), (4, 1), WIRE_CELL);
+
+            Scenario::new(
+                format!(
+                    "mixed with gravity {:?}, rigid bodies {}",
+                    gravity, rigid_bodies
+                ),
+                grid,
+            )
+            .run();
+        }
+    }
+}
+
+#[test]
+fn liquids_are_conserved_when_they_sink_and_spread() {
+    for (index, gravity) in gravities().into_iter().enumerate() {
+        let mut grid = new_grid(index as u64);
+        grid.gravity = gravity;
+        fill(&mut grid, (2, 2), (20, 3), LAVA_CELL);
+        fill(&mut grid, (2, 6), (20, 3), HONEY_CELL);
+        fill(&mut grid, (2, 10), (20, 3), WATER_CELL);
+        fill(&mut grid, (2, 14), (20, 3), OIL_CELL);
+        fill(&mut grid, (2, 18), (20, 3), SALT_WATER_CELL);
+
+        Scenario::new(format!("liquids with gravity {:?}", gravity), grid)
+            // Lava boils the water next to it into steam and burns oil
+            .reacting(&[WATER_CELL, STEAM_CELL, SALT_WATER_CELL, SALT_CELL, OIL_CELL])
+            .reacting(&[FIRE_CELL, SMOKE_CELL])
+            .run();
+    }
+}
+
+#[test]
+fn reactions_keep_their_totals() {
+    // Wet sand is sand that touched water, the water stays
+    let mut grid = new_grid(1);
+    fill(&mut grid, (4, 2), (16, 4), SAND_CELL);
+    fill(&mut grid, (4, 12), (16, 4), WATER_CELL);
+    Scenario::new("sand and water", grid)
+        .group(&[SAND_CELL, WET_SAND_CELL])
+        .run();
+
+    // Salt dissolves in water, one salt and one water make one salt water
+    let mut grid = new_grid(2);
+    fill(&mut grid, (4, 2), (16, 3), SALT_CELL);
+    fill(&mut grid, (4, 12), (16, 6), WATER_CELL);
+    Scenario::new("salt and water", grid)
+        .group(&[SALT_CELL, SALT_WATER_CELL])
+        .group(&[WATER_CELL, SALT_WATER_CELL])
+        .run();
+
+    // Voids only take what touches them and never take solids
+    let mut grid = new_grid(3);
+    fill(&mut grid, (4, 2), (16, 4), SAND_CELL);
+    fill(&mut grid, (4, 20), (16, 1), VOID_CELL);
+    fill(&mut grid, (2, 21), (20, 1), STEEL_CELL);
+    Scenario::new("sand into a void", grid)
+        .reacting(&[SAND_CELL])
+        .run();
+
+    // Clones copy the first thing that touches them and make more of it
+    let mut grid = new_grid(4);
+    fill(&mut grid, (10, 2), (4, 2), OIL_CELL);
+    fill(&mut grid, (8, 22), (8, 1), CLONE_CELL);
+    Scenario::new("oil onto a clone", grid)
+        .reacting(&[OIL_CELL])
+        .run();
+}
+
+#[test]
+fn particles_only_leave_through_void_edges() {
+    for (index, gravity) in gravities().into_iter().enumerate() {
+        let mut grid = new_grid(index as u64);
+        grid.gravity = gravity;
+        grid.boundaries = Boundaries {
+            left: Boundary::Void,
+            right: Boundary::Void,
+            top: Boundary::Void,
+            bottom: Boundary::Void,
+        };
+        fill(&mut grid, (6, 6), (12, 12), WATER_CELL);
+        fill(&mut grid, (2, 2), (4, 4), STEEL_CELL);
+
+        let mut last = counts(&grid);
+        for tick in 0..TICKS {
+            grid.update();
+            let now = counts(&grid);
+            // Water can only be lost, and nothing else changes
+            assert!(
+                now[WATER_CELL as usize] <= last[WATER_CELL as usize],
+                "water appeared with gravity {:?} at tick {}",
+                gravity,
+                tick
+            );
+            assert_eq!(now[STEEL_CELL as usize], 16);
+            assert_eq!(
+                now.iter().sum::<u32>() - now[EMPTY_CELL as usize],
+                now[WATER_CELL as usize] + 16
+            );
+            last = now;
+        }
+    }
+}
+
+#[test]
+fn falling_water_only_touches_the_cell_it_falls_into() {
+    let mut grid = new_grid(5);
+    grid.grid[4][10] = Cell::new_water();
+
+    grid.update();
+
+    assert_eq!(grid.grid[5][10].cell_type, WATER_CELL);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            assert_eq!(grid.is_processed(x, y), (x, y) == (10, 5), "{}, {}", x, y);
+        }
+    }
+}
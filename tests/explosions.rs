@@ -0,0 +1,211 @@
+use particle_sim::cell::*;
+use particle_sim::grid::Grid;
+
+mod common;
+use common::count;
+
+fn cell_type(grid: &Grid, x: i64, y: i64) -> u8 {
+    grid.grid[y as usize][x as usize].cell_type
+}
+
+#[test]
+fn steel_survives_a_blast() {
+    let mut grid = Grid::new(30, 30);
+    grid.seed(1);
+    for (x, y) in [(14, 15), (16, 15), (15, 14), (15, 16)] {
+        grid.grid[y][x] = Cell::new_steel();
+    }
+
+    grid.explode(15, 15, 8.0);
+
+    for (x, y) in [(14, 15), (16, 15), (15, 14), (15, 16)] {
+        assert_eq!(cell_type(&grid, x, y), STEEL_CELL);
+    }
+}
+
+#[test]
+fn blasts_get_weaker_with_distance() {
+    let mut grid = Grid::new(30, 30);
+    grid.seed(1);
+    // Glass to the right of the blast and sand to the left, both 1 to 9 cells away
+    for distance in 1..10 {
+        grid.grid[15][15 + distance] = Cell::new_glass();
+        grid.grid[15][15 - distance] = Cell::new_sand();
+    }
+
+    grid.explode(15, 15, 5.0);
+
+    // A cell breaks when the power left at its distance is more than its blast resistance
+    for distance in 1..10 {
+        let glass_broke = cell_type(&grid, 15 + distance, 15) != GLASS_CELL;
+        let sand_broke = cell_type(&grid, 15 - distance, 15) != SAND_CELL;
+        assert_eq!(
+            glass_broke,
+            5.0 - (distance as f32) > 2.0,
+            "glass {distance}"
+        );
+        assert_eq!(sand_broke, 5.0 - (distance as f32) > 1.0, "sand {distance}");
+    }
+}
+
+#[test]
+fn debris_gets_thrown_away_from_the_blast() {
+    let mut grid = Grid::new(30, 30);
+    grid.seed(1);
+    // Far enough away to not break but close enough to get thrown
+    let offsets = [(6, 0), (-6, 0), (0, 6), (0, -6), (4, 4)];
+    for (dx, dy) in offsets {
+        grid.grid[(15 + dy) as usize][(15 + dx) as usize] = Cell::new_sand();
+    }
+
+    grid.explode(15, 15, 4.0);
+
+    for (dx, dy) in offsets {
+        let cell = &grid.grid[(15 + dy) as usize][(15 + dx) as usize];
+        assert_eq!(cell.cell_type, SAND_CELL);
+        assert!(
+            cell.velocity_x * dx as f32 >= 0.0 && cell.velocity_y * dy as f32 >= 0.0,
+            "sand at {dx},{dy} got thrown towards the blast"
+        );
+        assert!(
+            cell.velocity_x != 0.0 || cell.velocity_y != 0.0,
+            "sand at {dx},{dy} didn't get thrown"
+        );
+        // Only along the line away from the center
+        assert!((cell.velocity_x * dy as f32 - cell.velocity_y * dx as f32).abs() < 1e-4);
+    }
+
+    // The blast throws what is closer harder
+    let near = grid.grid[15][21].velocity_x;
+    let mut far_grid = Grid::new(30, 30);
+    far_grid.grid[15][22] = Cell::new_sand();
+    far_grid.explode(15, 15, 4.0);
+    assert!(near > far_grid.grid[15][22].velocity_x);
+}
+
+#[test]
+fn fire_on_top_of_a_pile_sets_it_off() {
+    for seed in 1..5 {
+        let mut grid = Grid::new(40, 30);
+        grid.seed(seed);
+        for x in 0..40 {
+            grid.grid[29][x] = Cell::new_steel();
+        }
+        // 100 grains of gunpowder with fire dropped on top
+        for y in 19..29 {
+            for x in 15..25 {
+                grid.grid[y][x] = Cell::new_gunpowder();
+            }
+        }
+        for x in 15..25 {
+            grid.grid[18][x] = Cell::new_fire();
+        }
+
+        for _ in 0..200 {
+            grid.update();
+        }
+
+        assert_eq!(count(&grid, GUNPOWDER_CELL), 0, "seed {seed}");
+    }
+}
+
+#[test]
+fn chain_reaction_uses_up_a_box_of_gunpowder() {
+    for seed in 1..5 {
+        let mut grid = Grid::new(30, 30);
+        grid.seed(seed);
+        for i in 5..25 {
+            grid.grid[5][i] = Cell::new_steel();
+            grid.grid[24][i] = Cell::new_steel();
+            grid.grid[i][5] = Cell::new_steel();
+            grid.grid[i][24] = Cell::new_steel();
+        }
+        for y in 14..24 {
+            for x in 6..24 {
+                grid.grid[y][x] = Cell::new_gunpowder();
+            }
+        }
+        // One fire in the corner is enough to set all of it off
+        grid.grid[13][6] = Cell::new_fire();
+
+        for _ in 0..300 {
+            grid.update();
+        }
+
+        assert_eq!(count(&grid, GUNPOWDER_CELL), 0, "seed {seed}");
+    }
+}
+
+#[test]
+fn blasts_with_uneven_power_never_pull_debris_in() {
+    let mut grid = Grid::new(30, 30);
+    // 8 and 9 cells away, 9 is just past where a 4.3 blast still has force
+    grid.grid[15][23] = Cell::new_sand();
+    grid.grid[15][24] = Cell::new_sand();
+
+    grid.explode(15, 15, 4.3);
+
+    assert!(grid.grid[15][23].velocity_x > 0.0);
+    assert_eq!(grid.grid[15][24].velocity_x, 0.0);
+    assert_eq!(grid.grid[15][24].velocity_y, 0.0);
+}
+
+#[test]
+fn steel_walls_shield_what_is_behind_them() {
+    let mut grid = Grid::new(30, 30);
+    grid.seed(1);
+    // A closed steel box around the blast with sand all around the outside of it
+    for y in 10..21 {
+        for x in 10..21 {
+            grid.grid[y][x] = Cell::new_sand();
+        }
+    }
+    for i in 12..19 {
+        grid.grid[12][i] = Cell::new_steel();
+        grid.grid[18][i] = Cell::new_steel();
+        grid.grid[i][12] = Cell::new_steel();
+        grid.grid[i][18] = Cell::new_steel();
+    }
+    for y in 13..18 {
+        for x in 13..18 {
+            grid.grid[y][x] = Cell::new_empty();
+        }
+    }
+
+    grid.explode(15, 15, 8.0);
+
+    // Nothing outside the box broke, moved or caught fire
+    for y in 0..30 {
+        for x in 0..30 {
+            if (12..19).contains(&x) && (12..19).contains(&y) {
+                continue;
+            }
+            let cell = &grid.grid[y][x];
+            let expected = if (10..21).contains(&x) && (10..21).contains(&y) {
+                SAND_CELL
+            } else {
+                EMPTY_CELL
+            };
+            assert_eq!(cell.cell_type, expected, "cell at {x},{y}");
+            assert_eq!((cell.velocity_x, cell.velocity_y), (0.0, 0.0));
+        }
+    }
+    assert_eq!(count(&grid, STEEL_CELL), 24);
+}
+
+#[test]
+fn flying_fire_still_burns_out() {
+    let mut grid = Grid::new(100, 10);
+    grid.seed(1);
+    grid.gravity = (0, 0);
+    let mut fire = Cell::new_fire();
+    fire.velocity_x = 3.0;
+    grid.grid[5][2] = fire;
+
+    // Fire lives for 30 ticks, it is still flying long after that
+    for _ in 0..35 {
+        grid.update();
+    }
+
+    assert_eq!(count(&grid, FIRE_CELL), 0);
+}
@@ -0,0 +1,61 @@
+use particle_sim::cell::*;
+use particle_sim::grid::{Boundaries, Boundary, Grid};
+
+mod common;
+use common::count;
+
+fn wrapping_grid(size: i64) -> Grid {
+    let mut grid = Grid::new(size, size);
+    grid.seed(7);
+    grid.boundaries = Boundaries {
+        left: Boundary::Wrap,
+        right: Boundary::Wrap,
+        top: Boundary::Wrap,
+        bottom: Boundary::Wrap,
+    };
+    grid
+}
+
+#[test]
+fn smoke_in_wind_still_burns_out() {
+    let mut grid = wrapping_grid(40);
+    grid.wind = (1.0, 0.0);
+    grid.grid[20][20] = Cell::new_smoke();
+
+    // Smoke lives for 400 ticks, being pushed every tick doesn't stop it from aging
+    for _ in 0..410 {
+        grid.update();
+    }
+
+    assert_eq!(count(&grid, SMOKE_CELL), 0);
+}
+
+#[test]
+fn smoke_in_a_force_field_still_burns_out() {
+    let mut grid = wrapping_grid(40);
+    for row in &mut grid.force_field {
+        row.fill((0.0, -1.0));
+    }
+    grid.grid[20][20] = Cell::new_smoke();
+
+    for _ in 0..410 {
+        grid.update();
+    }
+
+    assert_eq!(count(&grid, SMOKE_CELL), 0);
+}
+
+#[test]
+fn pushed_gunpowder_still_catches_fire() {
+    let mut grid = Grid::new(20, 20);
+    grid.seed(7);
+    grid.gravity = (0, 0);
+    // Strong enough that the gunpowder gets pushed every tick
+    grid.wind = (3.0, 0.0);
+    grid.grid[10][10] = Cell::new_gunpowder();
+    grid.grid[11][10] = Cell::new_fire();
+
+    grid.update();
+
+    assert_eq!(count(&grid, GUNPOWDER_CELL), 0);
+}
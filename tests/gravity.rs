@@ -0,0 +1,99 @@
+use particle_sim::cell::*;
+use particle_sim::grid::{Grid, rotate_direction};
+
+mod common;
+use common::cells_of;
+
+#[test]
+fn sand_falls_up_when_gravity_is_flipped() {
+    let mut grid = Grid::new(5, 20);
+    grid.gravity = (0, -1);
+    grid.grid[15][2] = Cell::new_sand();
+
+    for _ in 0..30 {
+        grid.update();
+    }
+
+    assert_eq!(cells_of(&grid, SAND_CELL), vec![(2, 0)]);
+}
+
+#[test]
+fn water_gathers_on_the_wall_gravity_points_at() {
+    let mut grid = Grid::new(20, 6);
+    grid.gravity = (-1, 0);
+    for y in 0..6 {
+        grid.grid[y][12] = Cell::new_water();
+    }
+
+    for _ in 0..100 {
+        grid.update();
+    }
+
+    let water = cells_of(&grid, WATER_CELL);
+    assert_eq!(water.len(), 6);
+    assert!(water.iter().all(|&(x, _)| x == 0));
+}
+
+#[test]
+fn nothing_falls_without_gravity() {
+    let mut grid = Grid::new(10, 10);
+    grid.gravity = (0, 0);
+    grid.grid[2][3] = Cell::new_sand();
+    grid.grid[4][6] = Cell::new_water();
+
+    for _ in 0..20 {
+        grid.update();
+    }
+
+    assert_eq!(cells_of(&grid, SAND_CELL), vec![(3, 2)]);
+    assert_eq!(cells_of(&grid, WATER_CELL), vec![(6, 4)]);
+}
+
+// How far a grain of sand dropped from the top falls in 20 ticks
+fn distance_fallen(strength: f32) -> usize {
+    let mut grid = Grid::new(3, 60);
+    grid.seed(8);
+    grid.gravity_strength = strength;
+    grid.grid[0][1] = Cell::new_sand();
+
+    for _ in 0..20 {
+        grid.update();
+    }
+
+    cells_of(&grid, SAND_CELL)[0].1
+}
+
+#[test]
+fn weak_gravity_pulls_slower() {
+    assert_eq!(distance_fallen(1.0), 20);
+    let weak = distance_fallen(0.25);
+    assert!((1..10).contains(&weak), "fell {weak} cells");
+    assert_eq!(distance_fallen(0.0), 0);
+}
+
+#[test]
+fn strong_gravity_pulls_flying_things_harder() {
+    let mut speeds = Vec::new();
+    for strength in [1.0, 3.0] {
+        let mut grid = Grid::new(40, 40);
+        grid.gravity_strength = strength;
+        let mut sand = Cell::new_sand();
+        sand.velocity_x = 2.0;
+        grid.grid[5][5] = sand;
+
+        grid.update();
+
+        let (x, y) = cells_of(&grid, SAND_CELL)[0];
+        speeds.push(grid.grid[y][x].velocity_y);
+    }
+
+    assert!(speeds[1] > speeds[0] * 2.0, "{speeds:?}");
+}
+
+#[test]
+fn directions_turn_in_45_degree_steps() {
+    assert_eq!(rotate_direction((0, 1), 1), (-1, 1));
+    assert_eq!(rotate_direction((0, 1), -2), (1, 0));
+    assert_eq!(rotate_direction((1, -1), 1), (1, 0));
+    assert_eq!(rotate_direction((0, 0), 3), (0, 0));
+}
@@ -0,0 +1,29 @@
+use particle_sim::cell::*;
+use particle_sim::grid::{CHARGE_HEAD, Grid};
+
+#[test]
+fn inspecting_shows_the_state_of_the_cell() {
+    let mut grid = Grid::new(10, 10);
+    grid.grid[4][3] = Cell::new_source(OIL_CELL);
+    grid.grid[4][3].velocity_y = 1.5;
+    grid.charge[4][3] = CHARGE_HEAD;
+    grid.place_force(3, 4, (0.5, 0.0), 0);
+
+    let lines = grid.inspect(3, 4).unwrap();
+
+    assert_eq!(lines[0], "Source at 3, 4");
+    assert!(lines.contains(&format!("Type: {}", SOURCE_CELL)));
+    assert!(lines.contains(&"Velocity: 0.0, 1.5".to_string()));
+    assert!(lines.contains(&"Makes: Oil".to_string()));
+    assert!(lines.contains(&format!("Charge: {}", CHARGE_HEAD)));
+    assert!(lines.contains(&"Force: 0.5, 0.0".to_string()));
+}
+
+#[test]
+fn inspecting_outside_the_grid_shows_nothing() {
+    let grid = Grid::new(10, 10);
+
+    assert_eq!(grid.inspect(0, 0).unwrap()[0], "Empty at 0, 0");
+    assert!(grid.inspect(-1, 0).is_none());
+    assert!(grid.inspect(10, 3).is_none());
+}
@@ -0,0 +1,113 @@
+use particle_sim::cell::*;
+use particle_sim::grid::Grid;
+
+// Highest water cell between the giving columns
+fn water_level(grid: &Grid, from_x: i64, to_x: i64) -> Option<i64> {
+    (0..grid.height).find(|&y| {
+        (from_x..=to_x).any(|x| grid.grid[y as usize][x as usize].cell_type == WATER_CELL)
+    })
+}
+
+// Builds a U-tube out of steel with two arms that are connected at the bottom
+//
+//   #..#    #..#
+//   #..#    #..#
+//   #..######..#
+//   #..........#
+//   ############
+fn make_u_tube() -> Grid {
+    let mut grid = Grid::new(12, 30);
+
+    for y in 2..30 {
+        grid.grid[y][0] = Cell::new_steel();
+        grid.grid[y][11] = Cell::new_steel();
+    }
+    for x in 0..12 {
+        grid.grid[29][x] = Cell::new_steel();
+    }
+    for y in 2..27 {
+        for x in 3..9 {
+            grid.grid[y][x] = Cell::new_steel();
+        }
+    }
+
+    grid
+}
+
+#[test]
+fn u_tube_levels_out() {
+    let mut grid = make_u_tube();
+
+    // Fill the bottom and the left arm, the right arm starts empty
+    for x in 1..11 {
+        grid.grid[27][x] = Cell::new_water();
+        grid.grid[28][x] = Cell::new_water();
+    }
+    for y in 5..27 {
+        grid.grid[y][1] = Cell::new_water();
+        grid.grid[y][2] = Cell::new_water();
+    }
+
+    for _ in 0..600 {
+        grid.update();
+    }
+
+    let left = water_level(&grid, 1, 2).unwrap();
+    let right = water_level(&grid, 9, 10).unwrap();
+    assert!(
+        (left - right).abs() <= 1,
+        "left arm is at {left} but right arm is at {right}"
+    );
+    assert!(right < 20, "water never rose into the right arm");
+}
+
+#[test]
+fn flat_pool_stays_put() {
+    let mut grid = make_u_tube();
+
+    for x in 1..11 {
+        grid.grid[27][x] = Cell::new_water();
+        grid.grid[28][x] = Cell::new_water();
+    }
+
+    for _ in 0..100 {
+        grid.update();
+    }
+
+    assert_eq!(water_level(&grid, 1, 2), Some(27));
+    assert_eq!(water_level(&grid, 9, 10), Some(27));
+}
+
+// Drops a column of the giving liquid 4 wide and 30 high onto a flat floor and returns
+// how many columns of the floor it covers after the giving number of ticks
+fn spread_of_column(make_liquid: fn() -> Cell, ticks: usize) -> usize {
+    let mut grid = Grid::new(100, 32);
+    grid.seed(5);
+    for x in 0..100 {
+        grid.grid[31][x] = Cell::new_steel();
+    }
+    for y in 1..31 {
+        for x in 48..52 {
+            grid.grid[y][x] = make_liquid();
+        }
+    }
+
+    for _ in 0..ticks {
+        grid.update();
+    }
+
+    (0..100)
+        .filter(|&x| (0..31).any(|y| grid.grid[y][x].cell_type != EMPTY_CELL))
+        .count()
+}
+
+#[test]
+fn honey_spreads_slower_than_water() {
+    let water = spread_of_column(Cell::new_water, 60);
+    let honey = spread_of_column(Cell::new_honey, 60);
+
+    assert!(
+        honey * 2 < water,
+        "honey covers {honey} columns and water covers {water}"
+    );
+}
@@ -0,0 +1,51 @@
+use particle_sim::cell::*;
+use particle_sim::color::false_color;
+use particle_sim::grid::{CHUNK_SIZE, Grid};
+use particle_sim::overlay::ScalarField;
+
+#[test]
+fn only_chunks_where_something_moved_have_changed() {
+    let mut grid = Grid::new(32, 32);
+    grid.grid[2][20] = Cell::new_sand();
+    grid.grid[31][2] = Cell::new_steel();
+
+    grid.update();
+
+    assert_eq!(grid.changed_chunks(), vec![(20 / CHUNK_SIZE, 0)]);
+    assert!(grid.is_processed(20, 3));
+    assert!(!grid.is_processed(2, 31));
+}
+
+#[test]
+fn nothing_changes_in_a_world_that_stands_still() {
+    let mut grid = Grid::new(20, 20);
+    grid.grid[19][4] = Cell::new_steel();
+
+    grid.update();
+
+    assert!(grid.changed_chunks().is_empty());
+}
+
+#[test]
+fn heat_map_values_stay_between_0_and_1() {
+    let mut grid = Grid::new(4, 1);
+    grid.grid[0][0] = Cell::new_lava();
+    grid.grid[0][1] = Cell::new_oil();
+    grid.grid[0][2] = Cell::new_steel();
+    grid.grid[0][2].velocity_x = 100.0;
+
+    assert_eq!(ScalarField::Density.value(&grid, 0, 0), Some(1.0));
+    let oil = ScalarField::Density.value(&grid, 1, 0).unwrap();
+    assert!(oil > 0.0 && oil < 1.0);
+    assert_eq!(ScalarField::Speed.value(&grid, 2, 0), Some(1.0));
+    // Empty cells are left out of the heat map
+    assert_eq!(ScalarField::Density.value(&grid, 3, 0), None);
+}
+
+#[test]
+fn false_color_goes_from_blue_to_red() {
+    assert_eq!(false_color(0.0), [0.0, 0.0, 1.0, 1.0]);
+    assert_eq!(false_color(0.5), [0.0, 1.0, 0.0, 1.0]);
+    assert_eq!(false_color(1.0), [1.0, 0.0, 0.0, 1.0]);
+    assert_eq!(false_color(7.0), false_color(1.0));
+}
@@ -0,0 +1,75 @@
+use particle_sim::cell::*;
+use particle_sim::grid::Grid;
+
+// Pours a column of the giving powder onto a flat floor and lets it settle
+fn settled_pile(make_powder: fn() -> Cell) -> Grid {
+    let mut grid = Grid::new(80, 50);
+    grid.seed(11);
+    for x in 0..80 {
+        grid.grid[49][x] = Cell::new_steel();
+    }
+    for y in 9..49 {
+        for x in 39..41 {
+            grid.grid[y][x] = make_powder();
+        }
+    }
+
+    for _ in 0..300 {
+        grid.update();
+    }
+    grid
+}
+
+// Height and width of the pile
+fn pile_size(grid: &Grid) -> (i64, i64) {
+    let filled = |x: i64, y: i64| grid.grid[y as usize][x as usize].cell_type != EMPTY_CELL;
+    let height = (0..49).filter(|&y| (0..80).any(|x| filled(x, y))).count() as i64;
+    let width = (0..80).filter(|&x| (0..49).any(|y| filled(x, y))).count() as i64;
+    (height, width)
+}
+
+#[test]
+fn wet_sand_piles_steeper() {
+    let (dry_height, dry_width) = pile_size(&settled_pile(Cell::new_sand));
+    let (wet_height, wet_width) = pile_size(&settled_pile(Cell::new_wet_sand));
+
+    // The same amount of powder, so the steeper pile is both taller and thinner
+    assert!(
+        wet_height > dry_height * 3 / 2 && wet_width < dry_width,
+        "dry pile is {dry_height} high and {dry_width} wide, wet is {wet_height} by {wet_width}"
+    );
+}
+
+#[test]
+fn settled_piles_stay_put() {
+    let mut grid = settled_pile(Cell::new_sand);
+    let before = grid.to_ascii();
+
+    for _ in 0..100 {
+        grid.update();
+    }
+
+    assert_eq!(grid.to_ascii(), before);
+}
+
+#[test]
+fn resting_powder_waits_for_a_neighbour_to_move() {
+    let mut grid = Grid::new(20, 30);
+    grid.seed(4);
+    // A grain resting on top of a single steel cell, it could slide off either side
+    grid.grid[20][10] = Cell::new_steel();
+    grid.grid[19][10] = Cell::new_sand();
+    grid.grid[19][10].resting = true;
+
+    for _ in 0..50 {
+        grid.update();
+    }
+    assert_eq!(grid.grid[19][10].cell_type, SAND_CELL);
+
+    // A grain falling past right next to it wakes it up
+    grid.grid[5][11] = Cell::new_sand();
+    for _ in 0..20 {
+        grid.update();
+    }
+    assert_eq!(grid.grid[19][10].cell_type, EMPTY_CELL);
+}
@@ -0,0 +1,48 @@
+use particle_sim::cell::*;
+use particle_sim::grid::{Anchor, Grid};
+
+mod common;
+use common::count;
+
+#[test]
+fn growing_from_the_bottom_keeps_the_floor() {
+    let mut grid = Grid::new(6, 4);
+    grid.grid[3][0] = Cell::new_sand();
+    grid.grid[0][5] = Cell::new_steel();
+    grid.place_force(2, 1, (1.0, 0.0), 1);
+
+    grid.resize(10, 8, Anchor::Bottom);
+
+    assert_eq!((grid.width, grid.height), (10, 8));
+    assert_eq!(grid.grid.len(), 8);
+    assert!(grid.grid.iter().all(|row| row.len() == 10));
+    assert_eq!(grid.grid[7][2].cell_type, SAND_CELL);
+    assert_eq!(grid.grid[4][7].cell_type, STEEL_CELL);
+    assert_eq!(grid.force_field[5][4], (1.0, 0.0));
+
+    // The grid still updates after it was resized
+    grid.update();
+    assert_eq!(grid.grid[7][2].cell_type, SAND_CELL);
+}
+
+#[test]
+fn shrinking_cuts_off_what_does_not_fit() {
+    let mut grid = Grid::new(6, 6);
+    grid.grid[0][0] = Cell::new_steel();
+    grid.grid[5][5] = Cell::new_sand();
+
+    grid.resize(3, 3, Anchor::TopLeft);
+
+    assert_eq!(grid.grid[0][0].cell_type, STEEL_CELL);
+    assert_eq!(count(&grid, SAND_CELL), 0);
+}
+
+#[test]
+fn centered_resize_moves_everything_by_half_the_change() {
+    let mut grid = Grid::new(4, 4);
+    grid.grid[1][1] = Cell::new_steel();
+
+    grid.resize(8, 6, Anchor::Center);
+
+    assert_eq!(grid.grid[2][3].cell_type, STEEL_CELL);
+}
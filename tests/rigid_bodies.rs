@@ -0,0 +1,69 @@
+use particle_sim::cell::*;
+use particle_sim::grid::Grid;
+
+mod common;
+use common::{cells_of, count};
+
+// Places a steel L shape with its corner at the giving cords
+fn place_l_shape(grid: &mut Grid, x: usize, y: usize) {
+    for dy in 0..4 {
+        grid.grid[y + dy][x] = Cell::new_steel();
+    }
+    for dx in 1..4 {
+        grid.grid[y + 3][x + dx] = Cell::new_steel();
+    }
+}
+
+#[test]
+fn unsupported_body_falls_and_keeps_its_shape() {
+    let mut grid = Grid::new(10, 20);
+    grid.rigid_bodies = true;
+    place_l_shape(&mut grid, 3, 2);
+
+    let before = cells_of(&grid, STEEL_CELL);
+    for _ in 0..40 {
+        grid.update();
+    }
+    let after = cells_of(&grid, STEEL_CELL);
+
+    // The bottom of the L is at y = 5, so it should fall 14 cells to land on the floor
+    let expected: Vec<(usize, usize)> = before.iter().map(|&(x, y)| (x, y + 14)).collect();
+    assert_eq!(after, expected);
+}
+
+#[test]
+fn body_sinks_through_water() {
+    let mut grid = Grid::new(10, 20);
+    grid.rigid_bodies = true;
+    for y in 12..20 {
+        for x in 0..10 {
+            grid.grid[y][x] = Cell::new_water();
+        }
+    }
+    place_l_shape(&mut grid, 3, 2);
+
+    let water_before = count(&grid, WATER_CELL);
+    for _ in 0..60 {
+        grid.update();
+    }
+    let water_after = count(&grid, WATER_CELL);
+
+    assert_eq!(
+        cells_of(&grid, STEEL_CELL).iter().map(|&(_, y)| y).max(),
+        Some(19)
+    );
+    assert_eq!(water_before, water_after);
+}
+
+#[test]
+fn bodies_stay_put_when_rigid_bodies_are_off() {
+    let mut grid = Grid::new(10, 20);
+    place_l_shape(&mut grid, 3, 2);
+
+    let before = cells_of(&grid, STEEL_CELL);
+    for _ in 0..10 {
+        grid.update();
+    }
+
+    assert_eq!(cells_of(&grid, STEEL_CELL), before);
+}
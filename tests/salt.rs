@@ -0,0 +1,47 @@
+use particle_sim::cell::*;
+use particle_sim::grid::Grid;
+
+mod common;
+use common::count;
+
+#[test]
+fn salt_dissolves_and_boils_back_out() {
+    let mut grid = Grid::new(12, 12);
+    grid.seed(6);
+    // Without gravity so the cells stay where they are put
+    grid.gravity = (0, 0);
+    grid.grid[5][5] = Cell::new_salt();
+    grid.grid[5][6] = Cell::new_water();
+
+    grid.update();
+
+    assert_eq!(count(&grid, SALT_CELL), 0);
+    assert_eq!(count(&grid, WATER_CELL), 0);
+    assert_eq!(grid.grid[5][6].cell_type, SALT_WATER_CELL);
+
+    // Heating it leaves the salt behind where the salt water was and lets off steam
+    grid.grid[5][7] = Cell::new_lava();
+    grid.update();
+
+    assert_eq!(grid.grid[5][6].cell_type, SALT_CELL);
+    assert_eq!(count(&grid, SALT_WATER_CELL), 0);
+    assert_eq!(count(&grid, STEAM_CELL), 1);
+}
+
+#[test]
+fn fire_boils_salt_water_too() {
+    let mut grid = Grid::new(12, 12);
+    grid.seed(6);
+    grid.gravity = (0, 0);
+    for x in 3..8 {
+        grid.grid[5][x] = Cell::new_salt_water();
+    }
+    grid.grid[6][5] = Cell::new_fire();
+
+    grid.update();
+
+    // Only the salt water next to the fire boils
+    assert_eq!(count(&grid, SALT_CELL), 3);
+    assert_eq!(count(&grid, SALT_WATER_CELL), 2);
+    assert!(count(&grid, STEAM_CELL) > 0);
+}
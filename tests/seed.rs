@@ -0,0 +1,32 @@
+use particle_sim::cell::*;
+use particle_sim::grid::Grid;
+
+// A small world with a bit of everything that uses random numbers
+fn run_world(seed: u64) -> Vec<u8> {
+    let mut grid = Grid::new(30, 30);
+    grid.seed(seed);
+    grid.place_element(8, 5, SAND_CELL, 3);
+    grid.place_element(20, 5, WATER_CELL, 3);
+    grid.place_element(15, 25, FIRE_CELL, 2);
+    grid.place_element(15, 15, CO2_CELL, 2);
+
+    for _ in 0..60 {
+        grid.update();
+    }
+
+    grid.grid
+        .iter()
+        .flatten()
+        .map(|cell| cell.cell_type)
+        .collect()
+}
+
+#[test]
+fn same_seed_gives_the_same_world() {
+    assert_eq!(run_world(7), run_world(7));
+}
+
+#[test]
+fn different_seeds_give_different_worlds() {
+    assert_ne!(run_world(7), run_world(8));
+}
@@ -0,0 +1,187 @@
+// Golden snapshot tests, every scene is run for a number of ticks and what comes out
+// is compared to the snapshot in tests/snapshots
+//
+// After changing a rule on purpose, write the new snapshots with
+//     UPDATE_SNAPSHOTS=1 cargo test --test snapshots
+// and look over the diff of tests/snapshots before committing it
+
+use std::fs;
+use std::path::PathBuf;
+
+use particle_sim::grid::Grid;
+
+// Seed every scene runs with, so the random parts of the rules repeat exactly
+const SEED: u64 = 42;
+
+// Makes a grid out of ASCII art, see element_symbol for the characters
+fn parse_scene(scene: &str) -> Grid {
+    let mut grid = Grid::from_ascii(scene).unwrap();
+    grid.seed(SEED);
+    grid
+}
+
+// Runs the grid and compares it to the snapshot with the giving name
+fn check_grid(name: &str, mut grid: Grid, ticks: usize) {
+    for _ in 0..ticks {
+        grid.update();
+    }
+    let actual = grid.to_ascii();
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{}.txt", name));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "no snapshot at {}, make it with UPDATE_SNAPSHOTS=1",
+            path.display()
+        )
+    });
+    assert!(
+        actual == expected,
+        "{} doesn't match its snapshot after {} ticks\n\nexpected:\n{}\nactual:\n{}",
+        name,
+        ticks,
+        expected,
+        actual
+    );
+}
+
+fn check(name: &str, scene: &str, ticks: usize) {
+    check_grid(name, parse_scene(scene), ticks);
+}
+
+#[test]
+fn sand_piles_up() {
+    check(
+        "sand_piles_up",
+        "
+        ......SSS......
+        ......SSS......
+        ......SSS......
+        ......SSS......
+        ...............
+        ...............
+        ...............
+        ...............
+        ...............
+        ###############
+        ",
+        40,
+    );
+}
+
+#[test]
+fn water_fills_a_basin() {
+    check(
+        "water_fills_a_basin",
+        "
+        ...WWWW........
+        ...WWWW........
+        ...WWWW........
+        ...............
+        #.............#
+        #.............#
+        #.............#
+        ###############
+        ",
+        60,
+    );
+}
+
+#[test]
+fn liquids_layer_by_density() {
+    check(
+        "liquids_layer_by_density",
+        "
+        #YYYYYY#
+        #oooooo#
+        #WWWWWW#
+        #......#
+        ########
+        ",
+        80,
+    );
+}
+
+#[test]
+fn sand_gets_wet_and_sinks() {
+    check(
+        "sand_gets_wet_and_sinks",
+        "
+        ...SSS...
+        .........
+        #WWWWWWW#
+        #WWWWWWW#
+        #########
+        ",
+        30,
+    );
+}
+
+#[test]
+fn salt_dissolves_in_water() {
+    check(
+        "salt_dissolves_in_water",
+        "
+        ..NNN..
+        .......
+        #WWWWW#
+        #######
+        ",
+        20,
+    );
+}
+
+#[test]
+fn gases_rise_and_sink() {
+    check(
+        "gases_rise_and_sink",
+        "
+        ##########
+        #........#
+        #........#
+        #..HHDD..#
+        #..HHDD..#
+        #........#
+        #........#
+        ##########
+        ",
+        40,
+    );
+}
+
+#[test]
+fn fire_turns_sand_into_glass() {
+    check(
+        "fire_turns_sand_into_glass",
+        "
+        .........
+        ...FFF...
+        ..SSSSS..
+        #########
+        ",
+        10,
+    );
+}
+
+#[test]
+fn sand_falls_sideways_with_sideways_gravity() {
+    let mut grid = parse_scene(
+        "
+        #.......
+        #.......
+        #...SS..
+        #...SS..
+        #.......
+        ########
+        ",
+    );
+    grid.gravity = (-1, 0);
+    check_grid("sand_falls_sideways_with_sideways_gravity", grid, 20);
+}
@@ -0,0 +1,104 @@
+use particle_sim::cell::*;
+use particle_sim::grid::Grid;
+
+mod common;
+use common::{cells_of, count};
+
+// A world without gravity so nothing moves away from where it was put
+fn still_grid() -> Grid {
+    let mut grid = Grid::new(11, 11);
+    grid.seed(2);
+    grid.gravity = (0, 0);
+    grid
+}
+
+// The 8 cells around 5, 5
+fn ring() -> Vec<(usize, usize)> {
+    let mut cells = Vec::new();
+    for y in 4..7 {
+        for x in 4..7 {
+            if (x, y) != (5, 5) {
+                cells.push((x, y));
+            }
+        }
+    }
+    cells
+}
+
+#[test]
+fn source_fills_the_empty_cells_around_it() {
+    let mut grid = still_grid();
+    grid.grid[5][5] = Cell::new_source(SAND_CELL);
+    grid.grid[4][4] = Cell::new_steel();
+
+    grid.update();
+
+    let mut sand = ring();
+    sand.retain(|&cell| cell != (4, 4));
+    sand.sort_by_key(|&(x, y)| (y, x));
+    assert_eq!(cells_of(&grid, SAND_CELL), sand);
+    assert_eq!(grid.grid[4][4].cell_type, STEEL_CELL);
+    assert_eq!(grid.grid[5][5].cell_type, SOURCE_CELL);
+}
+
+#[test]
+fn source_without_a_element_does_nothing() {
+    let mut grid = still_grid();
+    grid.grid[5][5] = Cell::new_source(EMPTY_CELL);
+
+    for _ in 0..5 {
+        grid.update();
+    }
+
+    assert_eq!(count(&grid, EMPTY_CELL), 11 * 11 - 1);
+}
+
+#[test]
+fn void_deletes_whatever_touches_it() {
+    let mut grid = still_grid();
+    grid.grid[5][5] = Cell::new_void();
+    grid.grid[4][5] = Cell::new_sand();
+    grid.grid[5][6] = Cell::new_water();
+    grid.grid[6][4] = Cell::new_smoke();
+    grid.grid[4][4] = Cell::new_steel();
+    // Not touching it
+    grid.grid[5][8] = Cell::new_sand();
+
+    grid.update();
+
+    for (x, y) in ring() {
+        let expected = if (x, y) == (4, 4) {
+            STEEL_CELL
+        } else {
+            EMPTY_CELL
+        };
+        assert_eq!(grid.grid[y][x].cell_type, expected, "cell at {x},{y}");
+    }
+    assert_eq!(grid.grid[5][8].cell_type, SAND_CELL);
+}
+
+#[test]
+fn clone_copies_the_first_element_that_touches_it() {
+    let mut grid = still_grid();
+    grid.grid[5][5] = Cell::new_clone();
+    // Solids don't get copied
+    grid.grid[4][4] = Cell::new_steel();
+
+    grid.update();
+    assert_eq!(grid.grid[5][5].spawn_type, EMPTY_CELL);
+    assert_eq!(count(&grid, EMPTY_CELL), 11 * 11 - 2);
+
+    grid.grid[5][6] = Cell::new_water();
+    grid.update();
+
+    assert_eq!(grid.grid[5][5].spawn_type, WATER_CELL);
+    assert_eq!(count(&grid, WATER_CELL), 7);
+
+    // Once it has a element it keeps it, even with other elements touching it
+    grid.grid[4][5] = Cell::new_oxygen();
+    for _ in 0..5 {
+        grid.update();
+    }
+    assert_eq!(grid.grid[5][5].spawn_type, WATER_CELL);
+    assert_eq!(count(&grid, OXYGEN_CELL), 1);
+}
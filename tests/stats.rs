@@ -0,0 +1,38 @@
+use particle_sim::cell::*;
+use particle_sim::grid::Grid;
+
+#[test]
+fn stats_count_every_element() {
+    let mut grid = Grid::new(10, 10);
+    grid.grid[9][0] = Cell::new_sand();
+    grid.grid[9][1] = Cell::new_sand();
+    grid.grid[9][5] = Cell::new_steel();
+    grid.grid[0][5] = Cell::new_source(WATER_CELL);
+
+    let stats = grid.stats();
+
+    assert_eq!(stats.tick, 0);
+    assert_eq!(stats.count(SAND_CELL), 2);
+    assert_eq!(stats.count(STEEL_CELL), 1);
+    assert_eq!(stats.count(SOURCE_CELL), 1);
+    assert_eq!(stats.count(EMPTY_CELL), 96);
+    assert_eq!(stats.particles(), 4);
+}
+
+#[test]
+fn stats_follow_the_updates() {
+    let mut grid = Grid::new(10, 10);
+    grid.grid[0][5] = Cell::new_source(WATER_CELL);
+
+    for _ in 0..5 {
+        grid.update();
+    }
+    let stats = grid.stats();
+
+    assert_eq!(stats.tick, 5);
+    assert!(stats.count(WATER_CELL) > 0);
+    assert_eq!(
+        stats.particles(),
+        stats.count(WATER_CELL) + stats.count(SOURCE_CELL)
+    );
+}
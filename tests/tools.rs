@@ -0,0 +1,168 @@
+use particle_sim::cell::*;
+use particle_sim::grid::{Grid, Placement};
+use particle_sim::tools::{
+    Brush, BrushShape, Tool, ellipse_positions, line_positions, rectangle_positions,
+    stroke_positions,
+};
+
+mod common;
+use common::count;
+
+#[test]
+fn lines_have_no_gaps() {
+    let line = line_positions((2, 3), (11, -4));
+
+    assert_eq!(line.first(), Some(&(2, 3)));
+    assert_eq!(line.last(), Some(&(11, -4)));
+    // One cell for every step along the longest side
+    assert_eq!(line.len(), 10);
+    for pair in line.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        assert!((a.0 - b.0).abs() <= 1 && (a.1 - b.1).abs() <= 1);
+    }
+
+    assert_eq!(line_positions((5, 5), (5, 5)), vec![(5, 5)]);
+}
+
+#[test]
+fn rectangles_can_be_hollow_or_filled() {
+    let hollow = rectangle_positions((6, 4), (1, 1), false);
+    let filled = rectangle_positions((1, 1), (6, 4), true);
+
+    assert_eq!(filled.len(), 6 * 4);
+    assert_eq!(hollow.len(), 6 * 4 - 4 * 2);
+    assert!(hollow.contains(&(1, 1)) && hollow.contains(&(6, 4)));
+    assert!(!hollow.contains(&(3, 2)));
+}
+
+#[test]
+fn ellipses_fit_in_their_box() {
+    let filled = ellipse_positions((0, 0), (10, 6), true);
+    let outline = ellipse_positions((0, 0), (10, 6), false);
+
+    for &(x, y) in &filled {
+        assert!((0..=10).contains(&x) && (0..=6).contains(&y));
+        // Mirrored both ways it is the same ellipse
+        assert!(filled.contains(&(10 - x, y)) && filled.contains(&(x, 6 - y)));
+    }
+    // It touches every side of the box
+    assert!(filled.contains(&(0, 3)) && filled.contains(&(10, 3)));
+    assert!(filled.contains(&(5, 0)) && filled.contains(&(5, 6)));
+
+    assert!(outline.iter().all(|position| filled.contains(position)));
+    assert!(!outline.contains(&(5, 3)));
+    assert!(outline.len() < filled.len());
+}
+
+#[test]
+fn flood_fill_stops_at_walls() {
+    let mut grid = Grid::new(8, 8);
+    // A steel box with a empty inside of 3x3
+    for position in rectangle_positions((2, 2), (6, 6), false) {
+        grid.grid[position.1 as usize][position.0 as usize] = Cell::new_steel();
+    }
+
+    grid.flood_fill(4, 4, WATER_CELL, EMPTY_CELL);
+
+    let water = count(&grid, WATER_CELL);
+    assert_eq!(water, 9);
+    assert_eq!(grid.grid[0][0].cell_type, EMPTY_CELL);
+    assert_eq!(grid.grid[2][2].cell_type, STEEL_CELL);
+}
+
+#[test]
+fn tools_only_give_cells_inside_the_grid() {
+    let grid = Grid::new(10, 10);
+
+    let positions = Tool::FilledRectangle.positions(&grid, (-5, -5), (4, 4), circle(1));
+    assert_eq!(positions.len(), 25);
+
+    let positions = Tool::Line.positions(&grid, (0, 0), (9, 0), circle(2));
+    assert!(
+        positions
+            .iter()
+            .all(|&(x, y)| (0..10).contains(&x) && (0..10).contains(&y))
+    );
+
+    // The whole empty grid is one region
+    assert_eq!(
+        Tool::Fill.positions(&grid, (0, 0), (3, 3), circle(1)).len(),
+        100
+    );
+}
+
+#[test]
+fn brush_shapes() {
+    let grid = Grid::new(20, 20);
+
+    let square = Brush::new(BrushShape::Square, 2).positions(&grid, 10, 10);
+    assert_eq!(square.len(), 25);
+    assert!(square.contains(&(8, 8)) && square.contains(&(12, 12)));
+
+    let line = Brush::new(BrushShape::Line, 3).positions(&grid, 10, 10);
+    assert_eq!(line.len(), 7);
+    assert!(line.iter().all(|&(_, y)| y == 10));
+
+    // Cut off by the edge of the grid
+    let corner = Brush::new(BrushShape::Square, 2).positions(&grid, 0, 0);
+    assert_eq!(corner.len(), 9);
+}
+
+#[test]
+fn fast_strokes_are_continuous() {
+    let grid = Grid::new(60, 60);
+    // Two mouse positions far apart, like a fast drag between two events
+    let path = line_positions((5, 50), (55, 10));
+    let stroke = stroke_positions(&grid, &path, circle(1));
+
+    // Every cell the mouse went over is painted, and so is the brush around it
+    assert!(path.iter().all(|position| stroke.contains(position)));
+    assert!(stroke.contains(&(4, 50)) && stroke.contains(&(55, 11)));
+    // No cell shows up twice
+    let mut unique = stroke.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), stroke.len());
+}
+
+#[test]
+fn spraying_places_some_of_the_cells() {
+    let mut grid = Grid::new(40, 40);
+    grid.seed(3);
+    let positions = rectangle_positions((0, 0), (39, 39), true);
+    let spray = Placement {
+        density: 0.25,
+        replace: false,
+    };
+
+    grid.place_positions(&positions, STEEL_CELL, EMPTY_CELL, spray);
+
+    let steel = count(&grid, STEEL_CELL);
+    assert!(
+        (250..550).contains(&steel),
+        "sprayed {} of 1600 cells",
+        steel
+    );
+}
+
+#[test]
+fn replacing_draws_over_other_elements() {
+    let mut grid = Grid::new(10, 10);
+    let positions = rectangle_positions((0, 0), (9, 0), true);
+    grid.place_positions(&positions, STEEL_CELL, EMPTY_CELL, Placement::default());
+
+    grid.place_positions(&positions, SAND_CELL, EMPTY_CELL, Placement::default());
+    assert_eq!(count(&grid, STEEL_CELL), 10);
+
+    let replace = Placement {
+        replace: true,
+        ..Placement::default()
+    };
+    grid.place_positions(&positions, SAND_CELL, EMPTY_CELL, replace);
+    assert_eq!(count(&grid, SAND_CELL), 10);
+    assert_eq!(count(&grid, STEEL_CELL), 0);
+}
+
+fn circle(size: i32) -> Brush {
+    Brush::new(BrushShape::Circle, size)
+}
@@ -0,0 +1,96 @@
+use particle_sim::cell::*;
+use particle_sim::grid::{Boundaries, Boundary, Grid};
+use particle_sim::world::{load_world, save_world};
+use std::fs;
+use std::io::ErrorKind;
+
+mod common;
+use common::temp_path;
+
+#[test]
+fn world_round_trips_cells_and_settings() {
+    let mut grid = Grid::new(8, 6);
+    grid.grid[1][2] = Cell::new_sand();
+    grid.grid[5][0] = Cell::new_steel();
+    grid.grid[4][7] = Cell::new_source(OIL_CELL);
+    grid.grid[2][3] = Cell::new_smoke();
+    grid.grid[2][3].life_time = 42;
+    grid.wind = (0.5, -0.25);
+    grid.rigid_bodies = true;
+    grid.gravity = (-1, 0);
+    grid.gravity_strength = 0.5;
+    grid.boundaries = Boundaries {
+        left: Boundary::Wrap,
+        right: Boundary::Wrap,
+        top: Boundary::Wall,
+        bottom: Boundary::Void,
+    };
+    grid.place_force(4, 3, (1.0, 0.0), 1);
+
+    let path = temp_path("round-trip");
+    save_world(&grid, &path).unwrap();
+    let loaded = load_world(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!((loaded.width, loaded.height), (8, 6));
+    assert_eq!(loaded.wind, (0.5, -0.25));
+    assert!(loaded.rigid_bodies);
+    assert_eq!(loaded.gravity, (-1, 0));
+    assert_eq!(loaded.gravity_strength, 0.5);
+    assert_eq!(loaded.boundaries, grid.boundaries);
+    assert_eq!(loaded.force_field, grid.force_field);
+    for y in 0..6 {
+        for x in 0..8 {
+            let (a, b) = (&grid.grid[y][x], &loaded.grid[y][x]);
+            assert_eq!(
+                (a.cell_type, a.life_time, a.spawn_type),
+                (b.cell_type, b.life_time, b.spawn_type)
+            );
+        }
+    }
+}
+
+#[test]
+fn loading_a_broken_world_fails() {
+    let broken = [
+        ("short-row", "1"),
+        ("unknown-element", "99 1,5,200"),
+        ("unknown-spawn-type", "1 10,0,200"),
+        ("two-values", "1 3,4"),
+        ("four-values", "1 3,4,5,6"),
+    ];
+
+    for (name, cells) in broken {
+        let path = temp_path(name);
+        let text = format!("particle-sim world 1\nsize 2 1\ncells\n{}\nforces\n", cells);
+        fs::write(&path, text).unwrap();
+        let result = load_world(&path);
+        fs::remove_file(&path).unwrap();
+
+        match result {
+            Ok(_) => panic!("{} loaded", cells),
+            Err(error) => assert_eq!(error.kind(), ErrorKind::InvalidData, "{}", cells),
+        }
+    }
+
+    // A size that is too big to make is refused before anything gets made
+    let path = temp_path("huge");
+    fs::write(&path, "particle-sim world 1\nsize 100000 100000\ncells\n").unwrap();
+    let result = load_world(&path);
+    fs::remove_file(&path).unwrap();
+    match result {
+        Ok(_) => panic!("a 100000x100000 world loaded"),
+        Err(error) => assert_eq!(error.kind(), ErrorKind::InvalidData),
+    }
+
+    // The same world with a source that spawns sand is fine
+    let path = temp_path("source");
+    fs::write(
+        &path,
+        "particle-sim world 1\nsize 2 1\ncells\n1 10,0,1\nforces\n",
+    )
+    .unwrap();
+    let result = load_world(&path);
+    fs::remove_file(&path).unwrap();
+    assert!(result.is_ok());
+}